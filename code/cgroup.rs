@@ -0,0 +1,440 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::MonitorError;
+use crate::monitor::{CounterSet, CpuMetrics, MemoryMetrics};
+use crate::paths::{self, HostPaths};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CgroupMetrics {
+    pub path: String,
+    pub memory_current_bytes: Option<u64>,
+    pub memory_max_bytes: Option<u64>,
+    pub memory_high_bytes: Option<u64>,
+    pub memory_working_set_bytes: Option<u64>,
+    pub memory_used_percent: Option<f64>,
+    pub memory_events: Option<CgroupMemoryEvents>,
+    pub memory_stat: Option<CgroupMemoryStat>,
+    pub cpu: Option<CgroupCpu>,
+    pub io: Vec<CgroupIo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CgroupMemoryEvents {
+    pub low: u64,
+    pub high: u64,
+    pub max: u64,
+    pub oom: u64,
+    pub oom_kill: u64,
+    pub high_delta: u64,
+    pub oom_kill_delta: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CgroupMemoryStat {
+    pub anon_bytes: u64,
+    pub file_bytes: u64,
+    pub shmem_bytes: u64,
+    pub kernel_bytes: u64,
+    pub slab_bytes: u64,
+    pub sock_bytes: u64,
+    pub inactive_file_bytes: u64,
+    pub pgmajfault: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CgroupCpu {
+    pub usage_usec: u64,
+    pub user_usec: u64,
+    pub system_usec: u64,
+    pub nr_periods: u64,
+    pub nr_throttled: u64,
+    pub throttled_usec: u64,
+    pub limit_cores: Option<f64>,
+    pub usage_cores: f64,
+    pub usage_percent_of_limit: Option<f64>,
+    pub throttled_percent: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CgroupIo {
+    pub device: String,
+    pub rbytes: u64,
+    pub wbytes: u64,
+    pub rios: u64,
+    pub wios: u64,
+    pub rbytes_per_sec: f64,
+    pub wbytes_per_sec: f64,
+}
+
+impl CgroupMetrics {
+    // Re-base host-wide memory and CPU percentages on the cgroup's limits when it has any
+    pub fn apply_limits(&self, memory: &mut MemoryMetrics, cpu: &mut CpuMetrics) {
+        self.apply_memory_limit(memory);
+
+        if let Some(percent) = self.cpu.as_ref().and_then(|c| c.usage_percent_of_limit) {
+            cpu.cpu_usage_percent = percent;
+            cpu.cgroup_limited = true;
+        }
+    }
+
+    pub fn apply_memory_limit(&self, memory: &mut MemoryMetrics) {
+        if let (Some(max), Some(working_set)) = (self.memory_max_bytes, self.memory_working_set_bytes) {
+            let total_kb = max / 1024;
+            if total_kb < memory.total_kb {
+                let used_kb = working_set / 1024;
+                let current_kb = self.memory_current_bytes.unwrap_or(working_set) / 1024;
+                let stat = self.memory_stat.clone().unwrap_or_default();
+
+                memory.total_kb = total_kb;
+                memory.used_kb = used_kb;
+                memory.available_kb = total_kb.saturating_sub(used_kb);
+                memory.free_kb = total_kb.saturating_sub(current_kb);
+                memory.buffers_kb = 0;
+                memory.cached_kb = stat.file_bytes / 1024;
+                memory.used_percent = self.memory_used_percent.unwrap_or(0.0);
+                memory.cgroup_limited = true;
+            }
+        }
+    }
+}
+
+// dir comes from resolve_cgroup_dir at startup; files that disappear later just read as None
+pub fn collect_cgroup_metrics(dir: &Path, counters: &mut CounterSet) -> CgroupMetrics {
+    let file = |name: &str| fs::read_to_string(dir.join(name)).ok();
+
+    let memory_current_bytes = file("memory.current").and_then(|c| c.trim().parse().ok());
+    let memory_max_bytes = file("memory.max").and_then(|c| parse_limit(&c));
+    let memory_high_bytes = file("memory.high").and_then(|c| parse_limit(&c));
+
+    let memory_stat = file("memory.stat").map(|content| {
+        let mut stat = CgroupMemoryStat::default();
+        for (key, value) in flat_keyed(&content) {
+            match key {
+                "anon" => stat.anon_bytes = value,
+                "file" => stat.file_bytes = value,
+                "shmem" => stat.shmem_bytes = value,
+                "kernel" => stat.kernel_bytes = value,
+                "slab" => stat.slab_bytes = value,
+                "sock" => stat.sock_bytes = value,
+                "inactive_file" => stat.inactive_file_bytes = value,
+                "pgmajfault" => stat.pgmajfault = value,
+                _ => {}
+            }
+        }
+        stat
+    });
+
+    // same working set definition as the kubelet: page cache that can be dropped doesn't count
+    let memory_working_set_bytes = memory_current_bytes.map(|current: u64| {
+        current.saturating_sub(memory_stat.as_ref().map(|s| s.inactive_file_bytes).unwrap_or(0))
+    });
+    let memory_used_percent = match (memory_working_set_bytes, memory_max_bytes) {
+        (Some(used), Some(max)) if max > 0 => Some((used as f64 / max as f64) * 100.0),
+        _ => None,
+    };
+
+    let memory_events = file("memory.events").map(|content| {
+        let mut events = CgroupMemoryEvents::default();
+        for (key, value) in flat_keyed(&content) {
+            match key {
+                "low" => events.low = value,
+                "high" => events.high = value,
+                "max" => events.max = value,
+                "oom" => events.oom = value,
+                "oom_kill" => events.oom_kill = value,
+                _ => {}
+            }
+        }
+        events.high_delta = counters.delta("cgroup.memory.high", events.high).unwrap_or(0);
+        events.oom_kill_delta = counters.delta("cgroup.memory.oom_kill", events.oom_kill).unwrap_or(0);
+        events
+    });
+
+    let limit_cores = file("cpu.max").and_then(|content| {
+        let mut parts = content.split_whitespace();
+        let quota = parts.next()?.parse::<f64>().ok()?;
+        let period = parts.next()?.parse::<f64>().ok()?;
+        if period > 0.0 { Some(quota / period) } else { None }
+    });
+
+    let cpu = file("cpu.stat").map(|content| {
+        let mut cpu = CgroupCpu { limit_cores, ..CgroupCpu::default() };
+        for (key, value) in flat_keyed(&content) {
+            match key {
+                "usage_usec" => cpu.usage_usec = value,
+                "user_usec" => cpu.user_usec = value,
+                "system_usec" => cpu.system_usec = value,
+                "nr_periods" => cpu.nr_periods = value,
+                "nr_throttled" => cpu.nr_throttled = value,
+                "throttled_usec" => cpu.throttled_usec = value,
+                _ => {}
+            }
+        }
+
+        cpu.usage_cores = counters.rate("cgroup.cpu.usage_usec", cpu.usage_usec) / 1_000_000.0;
+        cpu.usage_percent_of_limit = limit_cores
+            .filter(|cores| *cores > 0.0)
+            .map(|cores| cpu.usage_cores / cores * 100.0);
+
+        let periods = counters.delta("cgroup.cpu.nr_periods", cpu.nr_periods).unwrap_or(0);
+        let throttled = counters.delta("cgroup.cpu.nr_throttled", cpu.nr_throttled).unwrap_or(0);
+        cpu.throttled_percent = if periods > 0 {
+            (throttled as f64 / periods as f64) * 100.0
+        } else { 0.0 };
+        cpu
+    });
+
+    let io = file("io.stat")
+        .map(|content| parse_io_stat(&content, counters))
+        .unwrap_or_default();
+
+    CgroupMetrics {
+        path: dir.display().to_string(),
+        memory_current_bytes,
+        memory_max_bytes,
+        memory_high_bytes,
+        memory_working_set_bytes,
+        memory_used_percent,
+        memory_events,
+        memory_stat,
+        cpu,
+        io,
+    }
+}
+
+// An explicit path may be absolute or relative to the cgroup2 mount; otherwise the monitor's own
+// cgroup from the "0::" line of /proc/self/cgroup (init's when reading a foreign procfs).
+// None means no cgroup v2 hierarchy is mounted.
+pub fn resolve_cgroup_dir(paths: &HostPaths, path_override: Option<&str>) -> Result<Option<PathBuf>, MonitorError> {
+    let mount = match cgroup2_mount(paths)? {
+        Some(mount) => mount,
+        None => return Ok(None),
+    };
+
+    let local_mount = paths.host_sys_path(&mount);
+
+    let dir = match path_override {
+        Some(path) if Path::new(path).is_absolute() && Path::new(path).starts_with(&mount) => {
+            paths.host_sys_path(Path::new(path))
+        }
+        Some(path) => join_relative(&local_mount, path),
+        None => {
+            let content = paths::read(&paths.host_process("cgroup"))?;
+            let own = content.lines()
+                .find_map(|line| line.strip_prefix("0::"))
+                .unwrap_or("/");
+            join_relative(&local_mount, own)
+        }
+    };
+
+    // only a cgroup asked for by name has to exist; a discovered one may not be visible under --sys-root
+    if !dir.is_dir() {
+        if path_override.is_none() {
+            return Ok(None);
+        }
+        return Err(MonitorError::FileRead(format!("--cgroup-path {}: no cgroup at {}",
+                                                  path_override.unwrap_or_default(), dir.display())));
+    }
+    Ok(Some(dir))
+}
+
+fn join_relative(mount: &Path, path: &str) -> PathBuf {
+    match path.trim_matches('/') {
+        "" => mount.to_path_buf(),
+        relative => mount.join(relative),
+    }
+}
+
+// the mount point as the host names it; callers rebase it onto --sys-root
+fn cgroup2_mount(paths: &HostPaths) -> Result<Option<PathBuf>, MonitorError> {
+    let content = paths::read(&paths.host_process("mounts"))?;
+
+    Ok(content.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+        .find(|parts| parts.len() > 2 && parts[2] == "cgroup2")
+        .map(|parts| PathBuf::from(parts[1])))
+}
+
+// "max" means unlimited
+fn parse_limit(content: &str) -> Option<u64> {
+    content.trim().parse().ok()
+}
+
+fn flat_keyed(content: &str) -> impl Iterator<Item = (&str, u64)> {
+    content.lines().filter_map(|line| {
+        let (key, value) = line.split_once(' ')?;
+        Some((key, value.trim().parse().ok()?))
+    })
+}
+
+fn parse_io_stat(content: &str, counters: &mut CounterSet) -> Vec<CgroupIo> {
+    let mut devices = Vec::new();
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let device = match parts.next() {
+            Some(device) => device.to_string(),
+            None => continue,
+        };
+
+        let mut io = CgroupIo {
+            device,
+            rbytes: 0,
+            wbytes: 0,
+            rios: 0,
+            wios: 0,
+            rbytes_per_sec: 0.0,
+            wbytes_per_sec: 0.0,
+        };
+        for field in parts {
+            let (key, value) = match field.split_once('=') {
+                Some((key, value)) => (key, value.parse::<u64>().unwrap_or(0)),
+                None => continue,
+            };
+            match key {
+                "rbytes" => io.rbytes = value,
+                "wbytes" => io.wbytes = value,
+                "rios" => io.rios = value,
+                "wios" => io.wios = value,
+                _ => {}
+            }
+        }
+
+        io.rbytes_per_sec = counters.rate(&format!("cgroup.io.{}.rbytes", io.device), io.rbytes);
+        io.wbytes_per_sec = counters.rate(&format!("cgroup.io.{}.wbytes", io.device), io.wbytes);
+        devices.push(io);
+    }
+
+    devices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::Fixture;
+
+    const MOUNTS: &str = "proc /proc proc rw 0 0\ncgroup2 /sys/fs/cgroup cgroup2 rw,nosuid 0 0\n";
+
+    fn cgroup_fixture(name: &str, files: &[(&str, &str)]) -> (Fixture, PathBuf) {
+        let fixture = Fixture::new(name, &[("proc/1/mounts", MOUNTS), ("proc/1/cgroup", "0::/app.slice\n")]);
+        for (file, content) in files {
+            fixture.write(&format!("sys/fs/cgroup/app.slice/{}", file), content);
+        }
+        let dir = resolve_cgroup_dir(&fixture.paths, None).unwrap().unwrap();
+        (fixture, dir)
+    }
+
+    fn host(total_kb: u64, cpu_percent: f64) -> (MemoryMetrics, CpuMetrics) {
+        let memory = serde_json::from_str(&format!(
+            r#"{{"total_kb": {0}, "available_kb": {0}, "used_kb": 0, "used_percent": 0.0,
+                "free_kb": {0}, "buffers_kb": 0, "cached_kb": 0}}"#, total_kb)).unwrap();
+        let cpu = serde_json::from_str(&format!(
+            r#"{{"user_time": 0, "system_time": 0, "idle_time": 0, "iowait_time": 0,
+                "total_time": 0, "cpu_usage_percent": {}}}"#, cpu_percent)).unwrap();
+        (memory, cpu)
+    }
+
+    #[test]
+    fn max_is_no_limit() {
+        assert_eq!(parse_limit("max\n"), None);
+        assert_eq!(parse_limit("536870912\n"), Some(536870912));
+    }
+
+    #[test]
+    fn flat_keyed_skips_lines_without_a_number() {
+        let pairs: Vec<(&str, u64)> = flat_keyed("anon 4096\nbroken\nfile x\nfile 8192\n").collect();
+        assert_eq!(pairs, vec![("anon", 4096), ("file", 8192)]);
+    }
+
+    #[test]
+    fn io_stat_is_per_device() {
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+        let content = "8:0 rbytes=1024 wbytes=2048 rios=1 wios=2 dbytes=0 dios=0\n\n259:0 rbytes=7 wbytes=bad\n";
+        let devices = parse_io_stat(content, &mut counters);
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].device, "8:0");
+        assert_eq!((devices[0].rbytes, devices[0].wbytes, devices[0].rios, devices[0].wios), (1024, 2048, 1, 2));
+        assert_eq!((devices[1].rbytes, devices[1].wbytes), (7, 0));
+    }
+
+    #[test]
+    fn working_set_leaves_out_inactive_page_cache() {
+        let (_fixture, dir) = cgroup_fixture("cgroup-working-set", &[
+            ("memory.current", "800\n"),
+            ("memory.max", "1000\n"),
+            ("memory.high", "max\n"),
+            ("memory.stat", "anon 500\nfile 300\ninactive_file 200\n"),
+            ("memory.events", "low 0\nhigh 3\nmax 1\noom 0\noom_kill 0\n"),
+            ("cpu.max", "200000 100000\n"),
+        ]);
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+        let metrics = collect_cgroup_metrics(&dir, &mut counters);
+
+        assert_eq!(metrics.memory_max_bytes, Some(1000));
+        assert_eq!(metrics.memory_high_bytes, None);
+        assert_eq!(metrics.memory_working_set_bytes, Some(600));
+        assert_eq!(metrics.memory_used_percent, Some(60.0));
+        assert_eq!(metrics.memory_events.unwrap().high, 3);
+        // no cpu.stat, so the cpu section is missing even though cpu.max is there
+        assert!(metrics.cpu.is_none());
+        assert!(metrics.io.is_empty());
+    }
+
+    #[test]
+    fn limits_replace_host_figures_only_when_tighter() {
+        let (_fixture, dir) = cgroup_fixture("cgroup-limits", &[
+            ("memory.current", "1048576\n"),
+            ("memory.max", "4194304\n"),
+            ("memory.stat", "file 524288\ninactive_file 0\n"),
+            ("cpu.max", "max 100000\n"),
+            ("cpu.stat", "usage_usec 1000\n"),
+        ]);
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+        let metrics = collect_cgroup_metrics(&dir, &mut counters);
+
+        let (mut memory, mut cpu) = host(16 * 1024 * 1024, 42.0);
+        metrics.apply_limits(&mut memory, &mut cpu);
+        assert!(memory.cgroup_limited);
+        assert_eq!((memory.total_kb, memory.used_kb, memory.available_kb), (4096, 1024, 3072));
+        assert_eq!(memory.cached_kb, 512);
+        assert_eq!(memory.used_percent, 25.0);
+        // an unlimited cpu.max leaves the host-wide percentage alone
+        assert!(!cpu.cgroup_limited);
+        assert_eq!(cpu.cpu_usage_percent, 42.0);
+
+        // a limit above what the host has is no limit at all
+        let (mut memory, mut cpu) = host(2048, 42.0);
+        metrics.apply_limits(&mut memory, &mut cpu);
+        assert!(!memory.cgroup_limited);
+        assert_eq!(memory.total_kb, 2048);
+    }
+
+    #[test]
+    fn cgroup_is_found_under_the_sys_root() {
+        let (fixture, dir) = cgroup_fixture("cgroup-resolve", &[("memory.current", "0\n")]);
+        assert_eq!(dir, fixture.root.join("sys/fs/cgroup/app.slice"));
+
+        let named = resolve_cgroup_dir(&fixture.paths, Some("/sys/fs/cgroup/app.slice")).unwrap();
+        assert_eq!(named, Some(dir.clone()));
+        let relative = resolve_cgroup_dir(&fixture.paths, Some("app.slice")).unwrap();
+        assert_eq!(relative, Some(dir));
+    }
+
+    #[test]
+    fn only_a_named_cgroup_has_to_exist() {
+        let fixture = Fixture::new("cgroup-missing", &[("proc/1/mounts", MOUNTS), ("proc/1/cgroup", "0::/gone.slice\n")]);
+        assert_eq!(resolve_cgroup_dir(&fixture.paths, None).unwrap(), None);
+
+        let err = resolve_cgroup_dir(&fixture.paths, Some("gone.slice")).unwrap_err().to_string();
+        assert!(err.contains("--cgroup-path gone.slice: no cgroup at"), "{}", err);
+
+        // no cgroup2 mount at all is not an error either way
+        fixture.write("proc/1/mounts", "proc /proc proc rw 0 0\n");
+        assert_eq!(resolve_cgroup_dir(&fixture.paths, Some("gone.slice")).unwrap(), None);
+    }
+}
@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use serde::{Deserialize, Serialize};
 
 #[derive(Parser, Debug, Clone, Serialize, Deserialize)]
@@ -10,18 +10,166 @@ pub struct Config {
     #[arg(short = 'l', long, default_value_t = 10)]
     pub leak_interval_sec: u64,
     
+    #[arg(long, default_value_t = false)]
+    pub leak_numa_report: bool,
+    
+    #[arg(long, value_enum, default_value = "linear")]
+    pub leak_profile: LeakProfile,
+    
+    #[arg(long, default_value_t = 2.0)]
+    pub leak_growth_factor: f64,
+    
+    #[arg(long, default_value_t = 1024)]
+    pub leak_peak_mb: usize,
+    
+    #[arg(long, default_value_t = 5)]
+    pub leak_hold_intervals: u64,
+    
+    #[arg(long, default_value_t = 0)]
+    pub leak_seed: u64,
+    
     #[arg(short = 'i', long, default_value_t = 60)]
     pub monitor_interval_sec: u64,
     
     #[arg(short = 'c', long, default_value_t = 2)]
     pub cpu_workers: usize,
     
+    #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u32).range(1..=100))]
+    pub cpu_target_percent: u32,
+    
+    #[arg(long, value_enum, default_value = "int")]
+    pub cpu_workload: CpuWorkload,
+    
+    #[arg(long, default_value_t = 65536)]
+    pub cpu_working_set_kb: usize,
+    
     #[arg(short = 'd', long, default_value_t = false)]
     pub disk_stress: bool,
     
+    // the system temp directory (TMPDIR, else /tmp) unless given; tmpfs there means no real disk I/O
+    #[arg(long, default_value_t = std::env::temp_dir().display().to_string())]
+    pub disk_stress_dir: String,
+    
+    #[arg(long, default_value_t = 1024)]
+    pub disk_block_kb: usize,
+    
+    #[arg(long, value_enum, default_value = "sequential")]
+    pub disk_pattern: DiskPattern,
+    
+    #[arg(long, default_value_t = false)]
+    pub disk_direct: bool,
+    
+    // total written over the whole run, which is also the size of the stress file
+    #[arg(long, default_value_t = 1024)]
+    pub disk_max_mb: u64,
+    
     #[arg(short = 's', long, default_value = "snapshots_incremental.json")]
     pub snapshot_file: String,
     
     #[arg(short = 'n', long, default_value_t = 1)]
     pub save_every_n_snapshots: usize,
+    
+    #[arg(long, default_value_t = false)]
+    pub disk_include_partitions: bool,
+    
+    #[arg(long, default_value_t = false)]
+    pub disk_include_virtual: bool,
+    
+    #[arg(long, value_delimiter = ',')]
+    pub net_include: Vec<String>,
+    
+    #[arg(long, value_delimiter = ',', default_value = "lo")]
+    pub net_exclude: Vec<String>,
+    
+    #[arg(long, default_value_t = 5)]
+    pub top_n: usize,
+    
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "rss,cpu")]
+    pub top_sort: Vec<ProcessSortKey>,
+    
+    #[arg(short = 'p', long, conflicts_with = "command")]
+    pub pid: Option<u32>,
+    
+    #[arg(last = true)]
+    pub command: Vec<String>,
+    
+    #[arg(long)]
+    pub cgroup_path: Option<String>,
+    
+    #[arg(long, default_value = "/proc")]
+    pub proc_root: String,
+    
+    #[arg(long, default_value = "/sys")]
+    pub sys_root: String,
+    
+    #[arg(long)]
+    pub guard_max_leak_mb: Option<u64>,
+    
+    #[arg(long)]
+    pub guard_min_available_mb: Option<u64>,
+    
+    #[arg(long)]
+    pub guard_max_swap_mb: Option<u64>,
+    
+    #[arg(long)]
+    pub guard_max_runtime_sec: Option<u64>,
+    
+    #[arg(long, value_enum, default_value = "pause")]
+    pub guard_action: GuardAction,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessSortKey {
+    Rss,
+    Cpu,
+    Threads,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiskPattern {
+    Sequential,
+    Random,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardAction {
+    Pause,
+    Release,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeakProfile {
+    Linear,
+    Exponential,
+    Sawtooth,
+    Bursts,
+    Step,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuWorkload {
+    #[default]
+    Int,
+    Float,
+    Memory,
+    Cache,
+    Branch,
+}
+
+impl CpuWorkload {
+    // what one unit of the worker's throughput counter means for this kind
+    pub fn unit(self) -> &'static str {
+        match self {
+            CpuWorkload::Int => "int ops",
+            CpuWorkload::Float => "flops",
+            CpuWorkload::Memory => "bytes",
+            CpuWorkload::Cache => "accesses",
+            CpuWorkload::Branch => "branches",
+        }
+    }
 }
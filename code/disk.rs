@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use crate::error::MonitorError;
+use crate::monitor::CounterSet;
+use crate::paths::{self, HostPaths};
+
+const SECTOR_SIZE: u64 = 512;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiskMetrics {
+    pub device: String,
+    pub reads_completed: u64,
+    pub writes_completed: u64,
+    pub sectors_read: u64,
+    pub sectors_written: u64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub read_latency_ms: f64,
+    pub write_latency_ms: f64,
+    pub in_flight: u64,
+    pub avg_queue_depth: f64,
+    pub util_percent: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiskFilter {
+    pub include_partitions: bool,
+    pub include_virtual: bool,
+}
+
+impl DiskFilter {
+    fn accepts(&self, paths: &HostPaths, device: &str) -> bool {
+        let is_partition = paths.sys(format!("class/block/{}/partition", device)).exists();
+        let is_virtual = paths.sys(format!("devices/virtual/block/{}", device)).exists();
+        (self.include_partitions || !is_partition) && (self.include_virtual || !is_virtual)
+    }
+}
+
+pub fn collect_disk_metrics(
+    paths: &HostPaths,
+    filter: DiskFilter,
+    counters: &mut CounterSet,
+) -> Result<Vec<DiskMetrics>, MonitorError> {
+    let content = paths::read(&paths.proc("diskstats"))?;
+    Ok(parse_diskstats(paths, &content, filter, counters))
+}
+
+fn parse_diskstats(paths: &HostPaths, content: &str, filter: DiskFilter, counters: &mut CounterSet) -> Vec<DiskMetrics> {
+    let elapsed_ms = counters.elapsed_secs() * 1000.0;
+    let mut disks = Vec::new();
+
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 14 { continue; }
+
+        let device = parts[2];
+        if !filter.accepts(paths, device) { continue; }
+
+        let field = |i: usize| parts[i].parse::<u64>().unwrap_or(0);
+        let reads = field(3);
+        let read_ms = field(6);
+        let writes = field(7);
+        let sectors_read = field(5);
+        let write_ms = field(10);
+        let sectors_written = field(9);
+        let in_flight = field(11);
+        let io_ms = field(12);
+        let weighted_io_ms = field(13);
+
+        let mut delta = |name: &str, value: u64| {
+            counters.delta(&format!("disk.{}.{}", device, name), value).unwrap_or(0)
+        };
+        let reads_delta = delta("reads", reads);
+        let writes_delta = delta("writes", writes);
+        let read_ms_delta = delta("read_ms", read_ms);
+        let write_ms_delta = delta("write_ms", write_ms);
+        let sectors_read_delta = delta("sectors_read", sectors_read);
+        let sectors_written_delta = delta("sectors_written", sectors_written);
+        let io_ms_delta = delta("io_ms", io_ms);
+        let weighted_io_ms_delta = delta("weighted_io_ms", weighted_io_ms);
+
+        let latency = |ms: u64, ios: u64| if ios > 0 { ms as f64 / ios as f64 } else { 0.0 };
+        let (avg_queue_depth, util_percent) = if elapsed_ms > 0.0 {
+            (weighted_io_ms_delta as f64 / elapsed_ms,
+             (io_ms_delta as f64 / elapsed_ms * 100.0).min(100.0))
+        } else { (0.0, 0.0) };
+
+        disks.push(DiskMetrics {
+            device: device.to_string(),
+            reads_completed: reads,
+            writes_completed: writes,
+            sectors_read,
+            sectors_written,
+            read_bytes_per_sec: counters.per_sec(sectors_read_delta * SECTOR_SIZE),
+            write_bytes_per_sec: counters.per_sec(sectors_written_delta * SECTOR_SIZE),
+            read_iops: counters.per_sec(reads_delta),
+            write_iops: counters.per_sec(writes_delta),
+            read_latency_ms: latency(read_ms_delta, reads_delta),
+            write_latency_ms: latency(write_ms_delta, writes_delta),
+            in_flight,
+            avg_queue_depth,
+            util_percent,
+        });
+    }
+
+    disks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::Fixture;
+
+    const DISKSTATS: &str = "\
+   8       0 sda 100 0 800 50 200 0 1600 150 0 120 200 0 0 0 0
+   8       1 sda1 90 0 720 45 190 0 1520 140 0 110 185 0 0 0 0
+ 259       0 nvme0n1 10 0 80 5 20 0 160 10 2 15 20
+   7       0 short 1 2 3
+";
+
+    const ALL: DiskFilter = DiskFilter { include_partitions: true, include_virtual: true };
+
+    #[test]
+    fn parses_diskstats_counters() {
+        let mut counters = CounterSet::default();
+        let disks = parse_diskstats(&HostPaths::default(), DISKSTATS, ALL, &mut counters);
+        let devices: Vec<&str> = disks.iter().map(|d| d.device.as_str()).collect();
+        assert_eq!(devices, ["sda", "sda1", "nvme0n1"]);
+
+        let sda = &disks[0];
+        assert_eq!((sda.reads_completed, sda.sectors_read), (100, 800));
+        assert_eq!((sda.writes_completed, sda.sectors_written), (200, 1600));
+        assert_eq!(disks[2].in_flight, 2);
+        // nothing to compare against yet
+        assert_eq!((sda.read_iops, sda.read_latency_ms), (0.0, 0.0));
+    }
+
+    #[test]
+    fn latency_comes_from_the_interval_deltas() {
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+        parse_diskstats(&HostPaths::default(), DISKSTATS, ALL, &mut counters);
+
+        // 10 more reads taking 40 ms in total, no writes
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        counters.begin_sample();
+        let disks = parse_diskstats(&HostPaths::default(),
+                                    &DISKSTATS.replace("sda 100 0 800 50", "sda 110 0 880 90"), ALL, &mut counters);
+
+        let sda = &disks[0];
+        assert_eq!(sda.read_latency_ms, 4.0);
+        assert_eq!(sda.write_latency_ms, 0.0);
+        assert!(sda.read_iops > 0.0 && sda.read_bytes_per_sec > 0.0);
+        assert_eq!(sda.write_iops, 0.0);
+    }
+
+    #[test]
+    fn partitions_and_virtual_devices_are_looked_up_in_the_sys_root() {
+        let fixture = Fixture::new("disk-filter", &[
+            ("proc/diskstats", DISKSTATS),
+            ("sys/class/block/sda1/partition", "1\n"),
+            ("sys/devices/virtual/block/nvme0n1/uevent", ""),
+        ]);
+        let names = |filter: DiskFilter| -> Vec<String> {
+            collect_disk_metrics(&fixture.paths, filter, &mut CounterSet::default())
+                .unwrap().into_iter().map(|d| d.device).collect()
+        };
+
+        assert_eq!(names(DiskFilter { include_partitions: false, include_virtual: false }), ["sda"]);
+        assert_eq!(names(DiskFilter { include_partitions: true, include_virtual: false }), ["sda", "sda1"]);
+        assert_eq!(names(ALL), ["sda", "sda1", "nvme0n1"]);
+    }
+}
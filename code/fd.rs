@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use crate::error::MonitorError;
+use crate::paths::{self, HostPaths};
+use crate::process::{self, ProcessTable};
+
+const GROWTH_SAMPLES: usize = 5;
+const MIN_GROWTH_FDS: u64 = 20;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FdMetrics {
+    pub allocated: u64,
+    pub max: u64,
+    pub used_percent: f64,
+    pub nr_open: u64,
+    pub processes: Vec<ProcessFdUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessFdUsage {
+    pub pid: u32,
+    pub comm: String,
+    pub fd_count: u64,
+    pub soft_limit: Option<u64>,
+    pub hard_limit: Option<u64>,
+    pub used_percent: Option<f64>,
+    pub growth: i64,
+    pub growing: bool,
+}
+
+// Remembers recent fd counts per process so steady growth shows up before the limit is hit
+#[derive(Debug, Default)]
+pub struct FdTracker {
+    history: HashMap<(u32, String), VecDeque<u64>>,
+}
+
+impl FdTracker {
+    pub fn collect(
+        &mut self,
+        paths: &HostPaths,
+        tables: &[ProcessTable],
+    ) -> Result<FdMetrics, MonitorError> {
+        // file-nr is "allocated  unused  max"; unused has been 0 since 2.6
+        let file_nr = paths::read(&paths.proc("sys/fs/file-nr"))?;
+        let fields: Vec<u64> = file_nr.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+        let allocated = fields.first().copied().unwrap_or(0);
+        let max = fields.get(2).copied().unwrap_or(0);
+        let nr_open = paths::read(&paths.proc("sys/fs/nr_open"))
+            .ok()
+            .and_then(|content| content.trim().parse().ok())
+            .unwrap_or(0);
+
+        let mut seen: Vec<(u32, String)> = Vec::new();
+        let mut processes = Vec::new();
+
+        for p in tables.iter().flat_map(|t| t.processes.iter()) {
+            let key = (p.pid, p.comm.clone());
+            if seen.contains(&key) { continue; }
+            seen.push(key.clone());
+
+            // other users' fd directories are unreadable without privileges; those are skipped
+            let fd_count = match process::count_fds(paths, p.pid) {
+                // listing our own fd directory holds one open itself
+                Ok(count) if paths.is_local() && p.pid == std::process::id() => count.saturating_sub(1),
+                Ok(count) => count,
+                Err(_) => continue,
+            };
+            let (soft_limit, hard_limit) = read_nofile_limit(paths, p.pid);
+
+            let history = self.history.entry(key).or_default();
+            history.push_back(fd_count);
+            if history.len() > GROWTH_SAMPLES {
+                history.pop_front();
+            }
+            let first = history.front().copied().unwrap_or(fd_count);
+            let growing = history.len() == GROWTH_SAMPLES
+                && history.iter().zip(history.iter().skip(1)).all(|(a, b)| b >= a)
+                && fd_count - first >= MIN_GROWTH_FDS;
+
+            processes.push(ProcessFdUsage {
+                pid: p.pid,
+                comm: p.comm.clone(),
+                fd_count,
+                soft_limit,
+                hard_limit,
+                used_percent: soft_limit
+                    .filter(|limit| *limit > 0)
+                    .map(|limit| (fd_count as f64 / limit as f64) * 100.0),
+                growth: fd_count as i64 - first as i64,
+                growing,
+            });
+        }
+
+        self.history.retain(|key, _| seen.contains(key));
+
+        Ok(FdMetrics {
+            allocated,
+            max,
+            used_percent: if max > 0 { (allocated as f64 / max as f64) * 100.0 } else { 0.0 },
+            nr_open,
+            processes,
+        })
+    }
+}
+
+// "Max open files            1024                 524288               files"; None is unlimited
+fn read_nofile_limit(paths: &HostPaths, pid: u32) -> (Option<u64>, Option<u64>) {
+    let content = match paths::read(&paths.pid(pid, "limits")) {
+        Ok(content) => content,
+        Err(_) => return (None, None),
+    };
+
+    content.lines()
+        .find_map(|line| line.strip_prefix("Max open files"))
+        .map(|rest| {
+            let mut values = rest.split_whitespace().map(|v| v.parse::<u64>().ok());
+            (values.next().flatten(), values.next().flatten())
+        })
+        .unwrap_or((None, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProcessSortKey;
+    use crate::paths::Fixture;
+    use crate::process::ProcessInfo;
+
+    const LIMITS: &str = "\
+Limit                     Soft Limit           Hard Limit           Units
+Max processes             63455                63455                processes
+Max open files            100                  unlimited            files
+";
+
+    fn table(pids: &[u32]) -> Vec<ProcessTable> {
+        let processes = pids.iter()
+            .map(|&pid| ProcessInfo {
+                pid,
+                comm: format!("proc{}", pid),
+                state: "S".into(),
+                rss_kb: 0,
+                cpu_percent: 0.0,
+                threads: 1,
+                uid: 0,
+            })
+            .collect();
+        vec![ProcessTable { sort_key: ProcessSortKey::Rss, processes }]
+    }
+
+    fn open_fds(fixture: &Fixture, pid: u32, count: u64) {
+        for fd in 0..count {
+            fixture.write(&format!("proc/{}/fd/{}", pid, fd), "");
+        }
+    }
+
+    #[test]
+    fn usage_is_measured_against_file_max_and_rlimit() {
+        let fixture = Fixture::new("fd-limits", &[
+            ("proc/sys/fs/file-nr", "2048\t0\t8192\n"),
+            ("proc/sys/fs/nr_open", "1048576\n"),
+            ("proc/7/limits", LIMITS),
+        ]);
+        open_fds(&fixture, 7, 85);
+        // pid 8 has no readable fd directory and is left out
+        let m = FdTracker::default().collect(&fixture.paths, &table(&[7, 8, 7])).unwrap();
+
+        assert_eq!((m.allocated, m.max, m.nr_open, m.used_percent), (2048, 8192, 1048576, 25.0));
+        assert_eq!(m.processes.len(), 1);
+        let p = &m.processes[0];
+        assert_eq!((p.fd_count, p.soft_limit, p.hard_limit, p.used_percent), (85, Some(100), None, Some(85.0)));
+    }
+
+    #[test]
+    fn steady_growth_is_flagged() {
+        let fixture = Fixture::new("fd-growth", &[("proc/sys/fs/file-nr", "100 0 1000\n")]);
+        let mut tracker = FdTracker::default();
+
+        let mut last = None;
+        for count in [10, 15, 20, 25, 30] {
+            open_fds(&fixture, 9, count);
+            last = Some(tracker.collect(&fixture.paths, &table(&[9])).unwrap());
+        }
+        let p = &last.unwrap().processes[0];
+        assert_eq!((p.fd_count, p.growth, p.soft_limit), (30, 20, None));
+        assert!(p.growing);
+
+        // a drop breaks the streak
+        std::fs::remove_file(fixture.root.join("proc/9/fd/29")).unwrap();
+        let m = tracker.collect(&fixture.paths, &table(&[9])).unwrap();
+        assert!(!m.processes[0].growing);
+    }
+
+    #[test]
+    fn missing_file_nr_is_an_error() {
+        let fixture = Fixture::new("fd-no-file-nr", &[]);
+        assert!(FdTracker::default().collect(&fixture.paths, &[]).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use crate::error::MonitorError;
+use crate::paths::{self, HostPaths};
+
+// statvfs on a hard-mounted share whose server is gone blocks until the server answers, and on
+// an autofs trigger point it mounts the share, so these are never looked at. Pseudo filesystems
+// (proc, sysfs, cgroup, ...) report zero blocks and drop out below; tmpfs and devtmpfs are kept
+// since /tmp, /run and /dev/shm fill up like any disk.
+const SKIPPED_FILESYSTEMS: &[&str] = &[
+    "autofs", "nfs", "nfs4", "cifs", "smb3", "smbfs", "ceph", "9p", "afs", "lustre",
+    "glusterfs", "fuse.glusterfs", "fuse.sshfs",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FilesystemMetrics {
+    pub device: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub read_only: bool,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub used_percent: f64,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+    pub inodes_free: u64,
+    pub inodes_used_percent: f64,
+}
+
+pub fn collect_filesystem_metrics(paths: &HostPaths) -> Result<Vec<FilesystemMetrics>, MonitorError> {
+    let content = paths::read(&paths.host_process("mounts"))?;
+    Ok(filesystems_from_mounts(paths, &content))
+}
+
+fn filesystems_from_mounts(paths: &HostPaths, content: &str) -> Vec<FilesystemMetrics> {
+    let mut filesystems: Vec<FilesystemMetrics> = Vec::new();
+    let mut seen: Vec<String> = Vec::new();
+
+    // mounts lists in mount order, so for an over-mounted path the last entry is the one
+    // statvfs actually sees; walking backwards keeps that one
+    for line in content.lines().rev() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 4 { continue; }
+
+        let mount_point = unescape_mount_field(parts[1]);
+        if seen.contains(&mount_point) { continue; }
+        seen.push(mount_point.clone());
+
+        let fs_type = parts[2];
+        if SKIPPED_FILESYSTEMS.contains(&fs_type) { continue; }
+
+        // mounts the monitor may not look into are skipped, not fatal
+        let st = match statvfs(&paths.host_root_path(&mount_point)) {
+            Some(st) => st,
+            None => continue,
+        };
+        if st.f_blocks == 0 { continue; }
+
+        let frsize = st.f_frsize;
+        let total_bytes = st.f_blocks * frsize;
+        let free_bytes = st.f_bfree * frsize;
+        let available_bytes = st.f_bavail * frsize;
+        let used_bytes = total_bytes.saturating_sub(free_bytes);
+        // like df: reserved blocks count as unavailable, not as used
+        let usable = used_bytes + available_bytes;
+        let used_percent = if usable > 0 {
+            (used_bytes as f64 / usable as f64) * 100.0
+        } else { 0.0 };
+
+        let inodes_total = st.f_files;
+        let inodes_free = st.f_ffree;
+        let inodes_used = inodes_total.saturating_sub(inodes_free);
+        let inodes_used_percent = if inodes_total > 0 {
+            (inodes_used as f64 / inodes_total as f64) * 100.0
+        } else { 0.0 };
+
+        let read_only = parts[3].split(',').any(|opt| opt == "ro")
+            || st.f_flag & libc::ST_RDONLY != 0;
+
+        filesystems.push(FilesystemMetrics {
+            device: unescape_mount_field(parts[0]),
+            mount_point,
+            fs_type: fs_type.to_string(),
+            read_only,
+            total_bytes,
+            used_bytes,
+            available_bytes,
+            used_percent,
+            inodes_total,
+            inodes_used,
+            inodes_free,
+            inodes_used_percent,
+        });
+    }
+
+    filesystems.reverse();
+    filesystems
+}
+
+fn statvfs(path: &Path) -> Option<libc::statvfs> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    unsafe {
+        let mut st: libc::statvfs = std::mem::zeroed();
+        if libc::statvfs(c_path.as_ptr(), &mut st) == 0 { Some(st) } else { None }
+    }
+}
+
+// The kernel escapes space, tab, newline and backslash in mount fields as \ooo octal
+fn unescape_mount_field(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4)
+            .filter(|digits| bytes[i] == b'\\' && digits.iter().all(|b| (b'0'..=b'7').contains(b)));
+        if let Some(digits) = octal {
+            let value = digits.iter().fold(0u32, |acc, d| acc * 8 + (d - b'0') as u32);
+            out.push(value as u8);
+            i += 4;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::Fixture;
+
+    fn mount_points(filesystems: &[FilesystemMetrics]) -> Vec<&str> {
+        filesystems.iter().map(|f| f.mount_point.as_str()).collect()
+    }
+
+    #[test]
+    fn unescapes_octal_sequences() {
+        assert_eq!(unescape_mount_field("/mnt/my\\040disk"), "/mnt/my disk");
+        assert_eq!(unescape_mount_field("/a\\011b\\012c\\134d"), "/a\tb\nc\\d");
+        assert_eq!(unescape_mount_field("/plain/path"), "/plain/path");
+        // not a full octal escape, kept as is
+        assert_eq!(unescape_mount_field("/x\\04"), "/x\\04");
+        assert_eq!(unescape_mount_field("/x\\089"), "/x\\089");
+    }
+
+    #[test]
+    fn over_mounted_path_reports_the_top_mount() {
+        let filesystems = filesystems_from_mounts(&HostPaths::default(), "/dev/old / ext4 rw 0 0\n/dev/new / xfs ro 0 0\n");
+
+        assert_eq!(filesystems.len(), 1);
+        assert_eq!(filesystems[0].device, "/dev/new");
+        assert_eq!(filesystems[0].fs_type, "xfs");
+        assert!(filesystems[0].read_only);
+    }
+
+    #[test]
+    fn zero_block_and_network_filesystems_are_skipped() {
+        let mounts = "\
+/dev/root / ext4 rw 0 0
+proc /proc proc rw 0 0
+sysfs /sys sysfs rw 0 0
+server:/export /mnt/nfs nfs4 rw 0 0
+systemd-1 /home autofs rw 0 0
+";
+        assert_eq!(mount_points(&filesystems_from_mounts(&HostPaths::default(), mounts)), ["/"]);
+    }
+
+    #[test]
+    fn tmpfs_is_reported() {
+        let filesystems = filesystems_from_mounts(&HostPaths::default(), "/dev/root / ext4 rw 0 0\ntmpfs /tmp tmpfs rw 0 0\n");
+        assert_eq!(mount_points(&filesystems), ["/", "/tmp"]);
+        assert_eq!(filesystems[1].fs_type, "tmpfs");
+    }
+
+    #[test]
+    fn foreign_mounts_are_read_through_init_root() {
+        let fixture = Fixture::new("filesystem-foreign", &[
+            ("proc/1/mounts", "/dev/sdb1 /data ext4 rw 0 0\n/dev/sdc1 /gone ext4 rw 0 0\n"),
+            ("proc/1/root/data/file", "x"),
+        ]);
+        let filesystems = collect_filesystem_metrics(&fixture.paths).unwrap();
+
+        // /data resolves under the fixture; /gone doesn't exist there and is left out
+        assert_eq!(mount_points(&filesystems), ["/data"]);
+        assert_eq!(filesystems[0].device, "/dev/sdb1");
+        assert!(filesystems[0].total_bytes > 0);
+    }
+}
@@ -0,0 +1,223 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::config::{Config, GuardAction};
+use crate::error::MonitorError;
+use crate::memory_leak;
+use crate::monitor::{self, CounterSet, CriticalEvent};
+use crate::paths::HostPaths;
+
+// Much shorter than a snapshot interval: a fast leak can eat gigabytes between two snapshots
+const TICK: Duration = Duration::from_secs(1);
+
+static TRIPPED: AtomicBool = AtomicBool::new(false);
+// GUARD_TRIGGERED events waiting to be attached to the next snapshot that gets recorded
+static PENDING_EVENTS: Mutex<Vec<CriticalEvent>> = Mutex::new(Vec::new());
+
+pub fn any_limit(config: &Config) -> bool {
+    config.guard_max_leak_mb.is_some()
+        || config.guard_min_available_mb.is_some()
+        || config.guard_max_swap_mb.is_some()
+        || config.guard_max_runtime_sec.is_some()
+}
+
+// enforced by the leak worker itself before every allocation, and by the guard's tick after
+pub fn max_leak_bytes(config: &Config) -> Option<u64> {
+    config.guard_max_leak_mb.map(|mb| mb.saturating_mul(1024 * 1024))
+}
+
+// The guard trips once and stays tripped: stressors are not resumed when the metric recovers,
+// and trips after the first are ignored.
+pub fn trip(reason: String, action: GuardAction) {
+    if TRIPPED.swap(true, Ordering::SeqCst) { return; }
+
+    let outcome = match action {
+        GuardAction::Pause => {
+            memory_leak::pause_stressors();
+            "stressors paused"
+        }
+        GuardAction::Release => {
+            memory_leak::release_stressors();
+            "stressors stopped and leaked memory released"
+        }
+    };
+    let description = format!("{}; {}", reason, outcome);
+    println!("[GUARD] {}", description);
+
+    if let Ok(mut events) = PENDING_EVENTS.lock() {
+        events.push(CriticalEvent {
+            event_type: "GUARD_TRIGGERED".into(),
+            severity: "CRITICAL".into(),
+            description,
+            timestamp: chrono::Local::now(),
+        });
+    }
+}
+
+pub fn take_events() -> Vec<CriticalEvent> {
+    PENDING_EVENTS.lock().map(|mut events| std::mem::take(&mut *events)).unwrap_or_default()
+}
+
+// Watches every limit on its own tick, so neither the snapshot interval nor a failing
+// collector elsewhere delays it
+#[derive(Debug)]
+pub struct StressGuard {
+    max_leak_bytes: Option<u64>,
+    min_available_kb: Option<u64>,
+    max_swap_kb: Option<u64>,
+    max_runtime_secs: Option<u64>,
+    action: GuardAction,
+    started: Instant,
+    paths: HostPaths,
+    cgroup_dir: Option<PathBuf>,
+    counters: CounterSet,
+}
+
+impl StressGuard {
+    // None when no limit is set
+    pub fn from_config(config: &Config, cgroup_dir: Option<&Path>) -> Option<Self> {
+        let guard = StressGuard {
+            max_leak_bytes: max_leak_bytes(config),
+            min_available_kb: config.guard_min_available_mb.map(|mb| mb.saturating_mul(1024)),
+            max_swap_kb: config.guard_max_swap_mb.map(|mb| mb.saturating_mul(1024)),
+            max_runtime_secs: config.guard_max_runtime_sec,
+            action: config.guard_action,
+            started: Instant::now(),
+            paths: HostPaths::new(&config.proc_root, &config.sys_root),
+            cgroup_dir: cgroup_dir.map(Path::to_path_buf),
+            counters: CounterSet::default(),
+        };
+        any_limit(config).then_some(guard)
+    }
+
+    fn check(&mut self, leak_bytes: u64) -> Result<Option<String>, MonitorError> {
+        let runtime = self.started.elapsed().as_secs();
+        if let Some(limit) = self.max_runtime_secs.filter(|limit| runtime >= *limit) {
+            return Ok(Some(format!("stressors ran {}s, limit {}s", runtime, limit)));
+        }
+        if let Some(limit) = self.max_leak_bytes.filter(|limit| leak_bytes > *limit) {
+            return Ok(Some(format!("leak holds {:.1} MB, limit {:.1} MB",
+                                   leak_bytes as f64 / (1024.0 * 1024.0), limit as f64 / (1024.0 * 1024.0))));
+        }
+        if self.min_available_kb.is_none() && self.max_swap_kb.is_none() {
+            return Ok(None);
+        }
+
+        // available is already re-based on memory.max inside a limited cgroup
+        self.counters.begin_sample();
+        let (memory, swap) = monitor::collect_memory_and_swap(&self.paths, self.cgroup_dir.as_deref(), &mut self.counters)?;
+
+        if let Some(floor) = self.min_available_kb.filter(|floor| memory.available_kb <= *floor) {
+            return Ok(Some(format!("{} {:.1} MB at or below floor {:.1} MB",
+                                   if memory.cgroup_limited { "cgroup available" } else { "MemAvailable" },
+                                   memory.available_kb as f64 / 1024.0, floor as f64 / 1024.0)));
+        }
+        if let Some(limit) = self.max_swap_kb.filter(|limit| swap.used_kb >= *limit) {
+            return Ok(Some(format!("swap used {:.1} MB, limit {:.1} MB",
+                                   swap.used_kb as f64 / 1024.0, limit as f64 / 1024.0)));
+        }
+        Ok(None)
+    }
+}
+
+pub fn spawn_stress_guard(running: Arc<AtomicBool>, mut guard: StressGuard) {
+    let mut failing = false;
+
+    while running.load(Ordering::SeqCst) && !TRIPPED.load(Ordering::SeqCst) {
+        match guard.check(memory_leak::leak_total_bytes()) {
+            Ok(Some(reason)) => trip(reason, guard.action),
+            Ok(None) => failing = false,
+            // reported once per run of failures rather than every tick
+            Err(e) => {
+                if !failing {
+                    eprintln!("[ERROR] Stress guard: {}", e);
+                }
+                failing = true;
+            }
+        }
+        thread::sleep(TICK);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use crate::paths::Fixture;
+
+    const MEMINFO: &str = "\
+MemTotal:        8000000 kB
+MemFree:         6000000 kB
+MemAvailable:    7000000 kB
+SwapTotal:       1000000 kB
+SwapFree:         900000 kB
+";
+
+    fn guard(fixture: &Fixture, args: &[&str], cgroup_dir: Option<&Path>) -> Option<StressGuard> {
+        let proc_root = fixture.paths.proc_root().display().to_string();
+        let mut argv = vec!["sysmon", "--proc-root", &proc_root];
+        argv.extend_from_slice(args);
+        StressGuard::from_config(&Config::parse_from(argv), cgroup_dir)
+    }
+
+    #[test]
+    fn no_limit_no_guard() {
+        let fixture = Fixture::new("guard-none", &[("proc/meminfo", MEMINFO)]);
+        assert!(guard(&fixture, &[], None).is_none());
+    }
+
+    #[test]
+    fn leak_limit_trips_on_held_bytes() {
+        // no meminfo: a leak limit alone never needs the memory collectors
+        let fixture = Fixture::new("guard-leak", &[]);
+        let mut g = guard(&fixture, &["--guard-max-leak-mb", "100"], None).unwrap();
+
+        assert_eq!(g.check(100 << 20).unwrap(), None);
+        let reason = g.check((100 << 20) + 1).unwrap().unwrap();
+        assert!(reason.starts_with("leak holds 100.0 MB, limit 100.0 MB"), "{}", reason);
+    }
+
+    #[test]
+    fn leak_limit_saturates() {
+        assert_eq!(max_leak_bytes(&Config::parse_from(["sysmon", "--guard-max-leak-mb", "100"])), Some(100 << 20));
+        // absurd limits saturate instead of overflowing
+        assert_eq!(max_leak_bytes(&Config::parse_from(["sysmon", "--guard-max-leak-mb", "18446744073709551615"])), Some(u64::MAX));
+    }
+
+    #[test]
+    fn host_memory_and_swap_limits() {
+        let fixture = Fixture::new("guard-host", &[("proc/meminfo", MEMINFO)]);
+
+        let mut g = guard(&fixture, &["--guard-min-available-mb", "1000"], None).unwrap();
+        assert_eq!(g.check(0).unwrap(), None);
+
+        let mut g = guard(&fixture, &["--guard-min-available-mb", "7000"], None).unwrap();
+        assert!(g.check(0).unwrap().unwrap().starts_with("MemAvailable"));
+
+        let mut g = guard(&fixture, &["--guard-max-swap-mb", "97"], None).unwrap();
+        assert!(g.check(0).unwrap().unwrap().starts_with("swap used"));
+    }
+
+    #[test]
+    fn available_floor_follows_the_cgroup_limit() {
+        let fixture = Fixture::new("guard-cgroup", &[
+            ("proc/meminfo", MEMINFO),
+            ("sys/fs/cgroup/app/memory.max", "1073741824\n"),
+            ("sys/fs/cgroup/app/memory.current", "1000000000\n"),
+            ("sys/fs/cgroup/app/memory.stat", "inactive_file 0\n"),
+        ]);
+        let cgroup_dir = fixture.root.join("sys/fs/cgroup/app");
+
+        // the host has ~6.8 GB available but the cgroup only ~70 MB
+        let mut g = guard(&fixture, &["--guard-min-available-mb", "100"], Some(&cgroup_dir)).unwrap();
+        assert!(g.check(0).unwrap().unwrap().starts_with("cgroup available"));
+    }
+
+    #[test]
+    fn unreadable_meminfo_is_an_error_not_a_trip() {
+        let fixture = Fixture::new("guard-missing", &[]);
+        let mut g = guard(&fixture, &["--guard-min-available-mb", "100"], None).unwrap();
+        assert!(g.check(0).is_err());
+    }
+}
@@ -1,6 +1,22 @@
 mod error;
+mod paths;
 mod memory_leak;
+mod guard;
+mod meminfo;
+mod numa;
 mod monitor;
+mod pressure;
+mod vmstat;
+mod disk;
+mod net;
+mod sockets;
+mod filesystem;
+mod fd;
+mod process;
+mod watch;
+mod leak_detect;
+mod cgroup;
+mod thermal;
 mod report;
 mod config;
 
@@ -18,15 +34,36 @@ fn main() -> Result<()> {
     let config = Config::parse();
 
     println!("=== SYSTEM MONITOR ===");
-    println!("[CONFIG] Memory leak: {} MB every {} seconds", 
-             config.leak_size_mb, config.leak_interval_sec);
+    println!("[CONFIG] Memory leak: {} MB every {} seconds, {:?} profile",
+             config.leak_size_mb, config.leak_interval_sec, config.leak_profile);
     println!("[CONFIG] Monitor interval: {} seconds", config.monitor_interval_sec);
-    println!("[CONFIG] CPU stress workers: {}", config.cpu_workers);
+    println!("[CONFIG] CPU stress workers: {} at {}% target, {:?} workload ({} KB working set)",
+             config.cpu_workers, config.cpu_target_percent, config.cpu_workload, config.cpu_working_set_kb);
+    if config.disk_stress {
+        println!("[CONFIG] Disk stress: {:?} {} KB blocks, {} MB in total, in {}{}",
+                 config.disk_pattern, config.disk_block_kb, config.disk_max_mb,
+                 config.disk_stress_dir, if config.disk_direct { " (O_DIRECT)" } else { "" });
+    }
+    if guard::any_limit(&config) {
+        let limit = |v: Option<u64>| v.map_or("-".to_string(), |v| v.to_string());
+        println!("[CONFIG] Stress guard: leak {} MB | min available {} MB | swap {} MB | runtime {} s -> {:?}",
+                 limit(config.guard_max_leak_mb), limit(config.guard_min_available_mb),
+                 limit(config.guard_max_swap_mb), limit(config.guard_max_runtime_sec),
+                 config.guard_action);
+    }
     println!("[CONFIG] Snapshot file: {}", config.snapshot_file);
+
+    // a bad --cgroup-path fails here, before any stressor or watched command is started
+    let mut collector = monitor::Collector::new(&config)?;
+    let mut watch_target = watch::WatchTarget::from_config(&config)?;
+    if let Some(target) = &watch_target {
+        println!("[CONFIG] Watching process tree of PID {} (stressors disabled)", target.pid);
+    }
     println!();
+    let stress_enabled = watch_target.is_none();
 
     let running = Arc::new(AtomicBool::new(true));
-    let snapshots = Arc::new(Mutex::new(Vec::new()));
+    let snapshots: Arc<Mutex<Vec<Snapshot>>> = Arc::new(Mutex::new(Vec::new()));
 
     {
         let r = running.clone();
@@ -37,8 +74,12 @@ fn main() -> Result<()> {
             for sig in signals.forever() {
                 println!("\n[!] Received signal {}, saving data and shutting down...", sig);
                 r.store(false, Ordering::SeqCst);
+                memory_leak::remove_disk_stress_files();
 
-                let snaps = snap_clone.lock().unwrap();
+                let mut snaps = snap_clone.lock().unwrap();
+                if let Some(last) = snaps.last_mut() {
+                    last.metrics.critical_events.extend(guard::take_events());
+                }
                 if !snaps.is_empty() {
                     println!("[EMERGENCY] Saving {} snapshots before termination...", snaps.len());
                     let report = FinalReport::new(snaps.clone(), cfg_clone.clone());
@@ -53,24 +94,49 @@ fn main() -> Result<()> {
         });
     }
 
-    let leak_running = running.clone();
-    let leak_config = config.clone();
-    let leak_handle = thread::spawn(move || {
-        memory_leak::spawn_leak_worker(leak_running, leak_config);
-    });
+    // started before the workers so the runtime limit counts from their start
+    let guard_handle = stress_enabled
+        .then(|| guard::StressGuard::from_config(&config, collector.cgroup_dir()))
+        .flatten()
+        .map(|stress_guard| {
+            let guard_running = running.clone();
+            thread::spawn(move || guard::spawn_stress_guard(guard_running, stress_guard))
+        });
+
+    let mut leak_handle = None;
+    if stress_enabled {
+        let leak_running = running.clone();
+        let leak_config = config.clone();
+        leak_handle = Some(thread::spawn(move || {
+            memory_leak::spawn_leak_worker(leak_running, leak_config);
+        }));
+    }
 
     let mut cpu_handles = Vec::new();
-    if config.cpu_workers > 0 {
+    if stress_enabled && config.cpu_workers > 0 {
         for i in 0..config.cpu_workers {
             let cpu_running = running.clone();
+            let target_percent = config.cpu_target_percent;
+            let workload = config.cpu_workload;
+            let working_set_kb = config.cpu_working_set_kb;
             let handle = thread::spawn(move || {
-                memory_leak::spawn_cpu_stress(cpu_running, i);
+                memory_leak::spawn_cpu_stress(cpu_running, i, target_percent, workload, working_set_kb);
             });
             cpu_handles.push(handle);
         }
     }
 
+    let mut disk_handle = None;
+    if stress_enabled && config.disk_stress {
+        let disk_running = running.clone();
+        let disk_config = config.clone();
+        disk_handle = Some(thread::spawn(move || {
+            memory_leak::spawn_disk_stress(disk_running, disk_config);
+        }));
+    }
+
     let reporter_running = running.clone();
+    let cpu_unit = config.cpu_workload.unit();
     let reporter_handle = thread::spawn(move || {
         fn fmt_bytes_gb(b: u64) -> f64 { (b as f64) / (1024.0 * 1024.0 * 1024.0) }
         fn fmt_big(n: u64) -> String {
@@ -85,13 +151,17 @@ fn main() -> Result<()> {
         }
 
         let interval = Duration::from_secs(5);
-        while reporter_running.load(Ordering::SeqCst) {
+        while stress_enabled && reporter_running.load(Ordering::SeqCst) {
             let leak_gb = fmt_bytes_gb(memory_leak::leak_total_bytes());
+            let freed_gb = fmt_bytes_gb(memory_leak::leak_freed_bytes());
             let workers = memory_leak::cpu_active_workers();
-            let cycles = memory_leak::cpu_total_cycles();
+            let ops = memory_leak::cpu_total_ops();
             println!(
-                "[STATUS] leak: {:.2} GB | cpu workers: {} | burned: {} cycles",
-                leak_gb, workers, fmt_big(cycles)
+                "[STATUS] leak: {:.2} GB (freed {:.2} GB) | cpu workers: {} | burned: {} {} | disk w {:.2} GB r {:.2} GB ({} fsyncs)",
+                leak_gb, freed_gb, workers, fmt_big(ops), cpu_unit,
+                fmt_bytes_gb(memory_leak::disk_write_bytes()),
+                fmt_bytes_gb(memory_leak::disk_read_bytes()),
+                memory_leak::disk_fsyncs()
             );
             thread::sleep(interval);
         }
@@ -100,15 +170,23 @@ fn main() -> Result<()> {
     thread::sleep(Duration::from_secs(2));
     println!("[MONITOR] Starting monitoring...\n");
 
+    if let Some(target) = &watch_target {
+        collector.watch(target.pid);
+    }
+    let target_alive = |target: &mut Option<watch::WatchTarget>| {
+        target.as_mut().is_none_or(|t| t.is_alive())
+    };
+
     let mut iteration = 0;
-    while running.load(Ordering::SeqCst) {
+    while running.load(Ordering::SeqCst) && target_alive(&mut watch_target) {
         iteration += 1;
         println!("=== Snapshot #{} at {} ===", 
                  iteration, 
                  chrono::Local::now().format("%H:%M:%S"));
 
-        match monitor::collect_metrics() {
-            Ok(metrics) => {
+        match collector.collect() {
+            Ok(mut metrics) => {
+                metrics.critical_events.extend(guard::take_events());
                 let snapshot = Snapshot::new(iteration, metrics);
                 snapshot.print_compact();
 
@@ -130,19 +208,38 @@ fn main() -> Result<()> {
         }
 
         for _ in 0..config.monitor_interval_sec {
-            if !running.load(Ordering::SeqCst) { break; }
+            if !running.load(Ordering::SeqCst) || !target_alive(&mut watch_target) { break; }
             thread::sleep(Duration::from_secs(1));
         }
     }
 
+    if let Some(status) = watch_target.as_ref().and_then(|t| t.exit_status()) {
+        println!("\n[WATCH] Target process exited: {}", status);
+    }
+
     println!("\n[*] Stopping workers...");
     running.store(false, Ordering::SeqCst);
     reporter_handle.join().ok();
+    if let Some(handle) = guard_handle {
+        handle.join().ok();
+    }
+    // a trip after the last snapshot still belongs in the report
+    let guard_events = guard::take_events();
+    if !guard_events.is_empty() {
+        if let Some(last) = snapshots.lock().unwrap().last_mut() {
+            last.metrics.critical_events.extend(guard_events);
+        }
+    }
 
-    leak_handle.join().expect("Leak worker thread panicked");
+    if let Some(handle) = leak_handle {
+        handle.join().expect("Leak worker thread panicked");
+    }
     for handle in cpu_handles {
         handle.join().expect("CPU worker thread panicked");
     }
+    if let Some(handle) = disk_handle {
+        handle.join().expect("Disk worker thread panicked");
+    }
 
     println!("\n[*] Generating final report from {} snapshots...", 
              snapshots.lock().unwrap().len());
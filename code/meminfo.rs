@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::error::MonitorError;
+use crate::paths::{self, HostPaths};
+
+// the memory and swap sections are computed from these; everything else may be missing
+const REQUIRED_KEYS: &[&str] = &["MemTotal", "MemFree", "MemAvailable", "SwapTotal", "SwapFree"];
+
+// /proc/meminfo as the kernel reports it; sizes are KB, HugePages_* are page counts
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MeminfoMetrics {
+    pub mem_total_kb: u64,
+    pub mem_free_kb: u64,
+    pub mem_available_kb: u64,
+    pub buffers_kb: u64,
+    pub cached_kb: u64,
+    pub swap_cached_kb: u64,
+    pub active_kb: u64,
+    pub inactive_kb: u64,
+    pub active_anon_kb: u64,
+    pub inactive_anon_kb: u64,
+    pub active_file_kb: u64,
+    pub inactive_file_kb: u64,
+    pub unevictable_kb: u64,
+    pub mlocked_kb: u64,
+    pub swap_total_kb: u64,
+    pub swap_free_kb: u64,
+    pub zswap_kb: u64,
+    pub zswapped_kb: u64,
+    pub dirty_kb: u64,
+    pub writeback_kb: u64,
+    pub anon_pages_kb: u64,
+    pub mapped_kb: u64,
+    pub shmem_kb: u64,
+    pub kreclaimable_kb: u64,
+    pub slab_kb: u64,
+    pub sreclaimable_kb: u64,
+    pub sunreclaim_kb: u64,
+    pub kernel_stack_kb: u64,
+    pub page_tables_kb: u64,
+    pub sec_page_tables_kb: u64,
+    pub nfs_unstable_kb: u64,
+    pub bounce_kb: u64,
+    pub writeback_tmp_kb: u64,
+    pub commit_limit_kb: u64,
+    pub committed_as_kb: u64,
+    pub vmalloc_total_kb: u64,
+    pub vmalloc_used_kb: u64,
+    pub vmalloc_chunk_kb: u64,
+    pub percpu_kb: u64,
+    pub hardware_corrupted_kb: u64,
+    pub anon_huge_pages_kb: u64,
+    pub shmem_huge_pages_kb: u64,
+    pub shmem_pmd_mapped_kb: u64,
+    pub file_huge_pages_kb: u64,
+    pub file_pmd_mapped_kb: u64,
+    pub huge_pages_total: u64,
+    pub huge_pages_free: u64,
+    pub huge_pages_rsvd: u64,
+    pub huge_pages_surp: u64,
+    pub hugepagesize_kb: u64,
+    pub hugetlb_kb: u64,
+    pub direct_map_4k_kb: u64,
+    pub direct_map_2m_kb: u64,
+    pub direct_map_1g_kb: u64,
+    // keys this kernel reports that have no field above (Balloon, CmaTotal, ...)
+    pub other: BTreeMap<String, u64>,
+    pub committed_percent: f64,
+    pub overcommit_mode: Option<u32>,
+}
+
+impl MeminfoMetrics {
+    // 2 is strict accounting: past CommitLimit allocations fail instead of being overcommitted
+    pub fn commit_enforced(&self) -> bool {
+        self.overcommit_mode == Some(2)
+    }
+}
+
+pub fn collect_meminfo(paths: &HostPaths) -> Result<MeminfoMetrics, MonitorError> {
+    let path = paths.proc("meminfo");
+    let content = paths::read(&path)?;
+
+    let mut m = MeminfoMetrics::default();
+    let mut seen: Vec<&str> = Vec::new();
+
+    // a line that doesn't parse is skipped on its own rather than costing the whole sample
+    for line in content.lines() {
+        let parsed = line.split_once(':')
+            .and_then(|(key, rest)| Some((key, rest.split_whitespace().next()?.parse::<u64>().ok()?)));
+        let (key, value) = match parsed {
+            Some(pair) => pair,
+            None => continue,
+        };
+        seen.push(key);
+
+        let field = match key {
+            "MemTotal" => &mut m.mem_total_kb,
+            "MemFree" => &mut m.mem_free_kb,
+            "MemAvailable" => &mut m.mem_available_kb,
+            "Buffers" => &mut m.buffers_kb,
+            "Cached" => &mut m.cached_kb,
+            "SwapCached" => &mut m.swap_cached_kb,
+            "Active" => &mut m.active_kb,
+            "Inactive" => &mut m.inactive_kb,
+            "Active(anon)" => &mut m.active_anon_kb,
+            "Inactive(anon)" => &mut m.inactive_anon_kb,
+            "Active(file)" => &mut m.active_file_kb,
+            "Inactive(file)" => &mut m.inactive_file_kb,
+            "Unevictable" => &mut m.unevictable_kb,
+            "Mlocked" => &mut m.mlocked_kb,
+            "SwapTotal" => &mut m.swap_total_kb,
+            "SwapFree" => &mut m.swap_free_kb,
+            "Zswap" => &mut m.zswap_kb,
+            "Zswapped" => &mut m.zswapped_kb,
+            "Dirty" => &mut m.dirty_kb,
+            "Writeback" => &mut m.writeback_kb,
+            "AnonPages" => &mut m.anon_pages_kb,
+            "Mapped" => &mut m.mapped_kb,
+            "Shmem" => &mut m.shmem_kb,
+            "KReclaimable" => &mut m.kreclaimable_kb,
+            "Slab" => &mut m.slab_kb,
+            "SReclaimable" => &mut m.sreclaimable_kb,
+            "SUnreclaim" => &mut m.sunreclaim_kb,
+            "KernelStack" => &mut m.kernel_stack_kb,
+            "PageTables" => &mut m.page_tables_kb,
+            "SecPageTables" => &mut m.sec_page_tables_kb,
+            "NFS_Unstable" => &mut m.nfs_unstable_kb,
+            "Bounce" => &mut m.bounce_kb,
+            "WritebackTmp" => &mut m.writeback_tmp_kb,
+            "CommitLimit" => &mut m.commit_limit_kb,
+            "Committed_AS" => &mut m.committed_as_kb,
+            "VmallocTotal" => &mut m.vmalloc_total_kb,
+            "VmallocUsed" => &mut m.vmalloc_used_kb,
+            "VmallocChunk" => &mut m.vmalloc_chunk_kb,
+            "Percpu" => &mut m.percpu_kb,
+            "HardwareCorrupted" => &mut m.hardware_corrupted_kb,
+            "AnonHugePages" => &mut m.anon_huge_pages_kb,
+            "ShmemHugePages" => &mut m.shmem_huge_pages_kb,
+            "ShmemPmdMapped" => &mut m.shmem_pmd_mapped_kb,
+            "FileHugePages" => &mut m.file_huge_pages_kb,
+            "FilePmdMapped" => &mut m.file_pmd_mapped_kb,
+            "HugePages_Total" => &mut m.huge_pages_total,
+            "HugePages_Free" => &mut m.huge_pages_free,
+            "HugePages_Rsvd" => &mut m.huge_pages_rsvd,
+            "HugePages_Surp" => &mut m.huge_pages_surp,
+            "Hugepagesize" => &mut m.hugepagesize_kb,
+            "Hugetlb" => &mut m.hugetlb_kb,
+            "DirectMap4k" => &mut m.direct_map_4k_kb,
+            "DirectMap2M" => &mut m.direct_map_2m_kb,
+            "DirectMap1G" => &mut m.direct_map_1g_kb,
+            _ => m.other.entry(key.to_string()).or_default(),
+        };
+        *field = value;
+    }
+
+    if let Some(key) = REQUIRED_KEYS.iter().find(|key| !seen.contains(key)) {
+        return Err(MonitorError::ParseError(format!("{}: no {}", path.display(), key)));
+    }
+
+    m.committed_percent = if m.commit_limit_kb > 0 {
+        (m.committed_as_kb as f64 / m.commit_limit_kb as f64) * 100.0
+    } else { 0.0 };
+    m.overcommit_mode = paths::read(&paths.proc("sys/vm/overcommit_memory"))
+        .ok()
+        .and_then(|content| content.trim().parse().ok());
+
+    Ok(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::Fixture;
+
+    const MEMINFO: &str = "\
+MemTotal:       16318412 kB
+MemFree:         1024000 kB
+MemAvailable:    8192000 kB
+Buffers:          204800 kB
+Cached:          4096000 kB
+SwapTotal:       2097148 kB
+SwapFree:        2097148 kB
+CommitLimit:    10256352 kB
+Committed_AS:   15384528 kB
+HugePages_Total:       4
+Hugepagesize:       2048 kB
+CmaTotal:          65536 kB
+";
+
+    #[test]
+    fn known_keys_fill_fields_and_the_rest_is_kept() {
+        let fixture = Fixture::new("meminfo", &[("proc/meminfo", MEMINFO), ("proc/sys/vm/overcommit_memory", "2\n")]);
+        let m = collect_meminfo(&fixture.paths).unwrap();
+
+        assert_eq!((m.mem_total_kb, m.mem_available_kb, m.cached_kb), (16318412, 8192000, 4096000));
+        assert_eq!((m.huge_pages_total, m.hugepagesize_kb), (4, 2048));
+        assert_eq!(m.other.get("CmaTotal"), Some(&65536));
+        assert_eq!(m.committed_percent, 15384528.0 / 10256352.0 * 100.0);
+        assert!(m.commit_enforced());
+    }
+
+    #[test]
+    fn bad_lines_are_skipped() {
+        let content = format!("{}Garbage line\nDirty:           bogus kB\nWriteback:\n", MEMINFO);
+        let fixture = Fixture::new("meminfo-bad-lines", &[("proc/meminfo", &content)]);
+        let m = collect_meminfo(&fixture.paths).unwrap();
+
+        assert_eq!((m.mem_total_kb, m.dirty_kb, m.writeback_kb), (16318412, 0, 0));
+        assert!(!m.other.contains_key("Garbage line"));
+        // no overcommit_memory in this procfs
+        assert_eq!(m.overcommit_mode, None);
+    }
+
+    #[test]
+    fn missing_required_key_is_an_error() {
+        let content = MEMINFO.replace("MemAvailable:    8192000 kB\n", "MemAvailable:    ? kB\n");
+        let fixture = Fixture::new("meminfo-missing", &[("proc/meminfo", &content)]);
+        let err = collect_meminfo(&fixture.paths).unwrap_err().to_string();
+        assert!(err.contains("meminfo: no MemAvailable"), "{}", err);
+    }
+}
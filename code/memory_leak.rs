@@ -1,14 +1,125 @@
-use std::sync::{Arc, atomic::{AtomicBool, AtomicU64, Ordering}};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::os::unix::fs::{FileExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU64, Ordering}};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use crate::config::{CpuWorkload, DiskPattern, LeakProfile};
 
+// bytes the leak worker currently holds; what it has handed back is counted separately
 static LEAK_TOTAL_BYTES: AtomicU64 = AtomicU64::new(0);
-static CPU_TOTAL_CYCLES: AtomicU64 = AtomicU64::new(0);
+static LEAK_FREED_BYTES: AtomicU64 = AtomicU64::new(0);
+static CPU_TOTAL_OPS: AtomicU64 = AtomicU64::new(0);
 static CPU_ACTIVE_WORKERS: AtomicU64 = AtomicU64::new(0);
+static DISK_WRITE_BYTES: AtomicU64 = AtomicU64::new(0);
+static DISK_READ_BYTES: AtomicU64 = AtomicU64::new(0);
+static DISK_FSYNCS: AtomicU64 = AtomicU64::new(0);
+static CPU_WORKER_STATS: Mutex<Vec<CpuWorkerStats>> = Mutex::new(Vec::new());
+// files the disk worker created, so a signal-driven exit can still remove them
+static DISK_STRESS_FILES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+// leaked bytes per NUMA node, indexed by node id; only filled with --leak-numa-report
+static LEAK_NODE_BYTES: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+// set by the stress guard; paused workers idle, released ones free what they hold and exit
+static STRESS_PAUSED: AtomicBool = AtomicBool::new(false);
+static STRESS_RELEASED: AtomicBool = AtomicBool::new(false);
 
 pub fn leak_total_bytes() -> u64 { LEAK_TOTAL_BYTES.load(Ordering::Relaxed) }
-pub fn cpu_total_cycles() -> u64 { CPU_TOTAL_CYCLES.load(Ordering::Relaxed) }
+pub fn leak_freed_bytes() -> u64 { LEAK_FREED_BYTES.load(Ordering::Relaxed) }
+pub fn cpu_total_ops() -> u64 { CPU_TOTAL_OPS.load(Ordering::Relaxed) }
 pub fn cpu_active_workers() -> u64 { CPU_ACTIVE_WORKERS.load(Ordering::Relaxed) }
+pub fn disk_write_bytes() -> u64 { DISK_WRITE_BYTES.load(Ordering::Relaxed) }
+pub fn disk_read_bytes() -> u64 { DISK_READ_BYTES.load(Ordering::Relaxed) }
+pub fn disk_fsyncs() -> u64 { DISK_FSYNCS.load(Ordering::Relaxed) }
+
+pub fn pause_stressors() { STRESS_PAUSED.store(true, Ordering::SeqCst); }
+pub fn release_stressors() { STRESS_RELEASED.store(true, Ordering::SeqCst); }
+pub fn stressors_paused() -> bool { STRESS_PAUSED.load(Ordering::SeqCst) }
+pub fn stressors_released() -> bool { STRESS_RELEASED.load(Ordering::SeqCst) }
+
+// Blocks while stressors are paused; false once the worker should stop for good
+fn stress_may_continue(running: &AtomicBool) -> bool {
+    while stressors_paused() && !stressors_released() && running.load(Ordering::SeqCst) {
+        thread::sleep(Duration::from_millis(200));
+    }
+    running.load(Ordering::SeqCst) && !stressors_released()
+}
+
+pub fn leak_node_bytes() -> Vec<LeakNodeBytes> {
+    let nodes = LEAK_NODE_BYTES.lock().map(|n| n.clone()).unwrap_or_default();
+    nodes.into_iter()
+        .enumerate()
+        .filter(|(_, bytes)| *bytes > 0)
+        .map(|(node, bytes)| LeakNodeBytes { node: node as u32, bytes })
+        .collect()
+}
+
+pub fn cpu_worker_stats() -> Vec<CpuWorkerStats> {
+    CPU_WORKER_STATS.lock().map(|w| w.clone()).unwrap_or_default()
+}
+
+// achieved is the worker thread's own CPU time over its wall time since it started;
+// ops are counted in the workload's own unit, see CpuWorkload::unit
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CpuWorkerStats {
+    pub worker: usize,
+    pub target_percent: u32,
+    pub cpu_secs: f64,
+    pub wall_secs: f64,
+    pub achieved_percent: f64,
+    #[serde(default)]
+    pub workload: CpuWorkload,
+    #[serde(default)]
+    pub ops: u64,
+    #[serde(default)]
+    pub ops_per_cpu_sec: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeakNodeBytes {
+    pub node: u32,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StressCounters {
+    pub leak_total_bytes: u64,
+    #[serde(default)]
+    pub leak_freed_bytes: u64,
+    #[serde(default, alias = "cpu_total_cycles")]
+    pub cpu_total_ops: u64,
+    pub cpu_active_workers: u64,
+    #[serde(default)]
+    pub leak_node_bytes: Vec<LeakNodeBytes>,
+    #[serde(default)]
+    pub cpu_workers: Vec<CpuWorkerStats>,
+    #[serde(default)]
+    pub disk_write_bytes: u64,
+    #[serde(default)]
+    pub disk_read_bytes: u64,
+    #[serde(default)]
+    pub disk_fsyncs: u64,
+    #[serde(default)]
+    pub stressors_paused: bool,
+    #[serde(default)]
+    pub stressors_released: bool,
+}
+
+pub fn stress_counters() -> StressCounters {
+    StressCounters {
+        leak_total_bytes: leak_total_bytes(),
+        leak_freed_bytes: leak_freed_bytes(),
+        cpu_total_ops: cpu_total_ops(),
+        cpu_active_workers: cpu_active_workers(),
+        leak_node_bytes: leak_node_bytes(),
+        cpu_workers: cpu_worker_stats(),
+        disk_write_bytes: disk_write_bytes(),
+        disk_read_bytes: disk_read_bytes(),
+        disk_fsyncs: disk_fsyncs(),
+        stressors_paused: stressors_paused(),
+        stressors_released: stressors_released(),
+    }
+}
 
 #[inline]
 fn page_size() -> usize {
@@ -19,44 +130,565 @@ fn page_size() -> usize {
     }
 }
 
+struct LeakChunk {
+    data: Vec<u8>,
+    // bytes per NUMA node, subtracted again when the chunk is released
+    nodes: Vec<u64>,
+}
+
+// Allocations this large are mmap'd by the allocator, so dropping a chunk really returns it
+// to the kernel and RSS falls with LEAK_TOTAL_BYTES.
+fn release_leak(held: &mut Vec<LeakChunk>) {
+    for chunk in held.drain(..) {
+        let len = chunk.data.len() as u64;
+        LEAK_TOTAL_BYTES.fetch_sub(len, Ordering::Relaxed);
+        LEAK_FREED_BYTES.fetch_add(len, Ordering::Relaxed);
+        if let Ok(mut nodes) = LEAK_NODE_BYTES.lock() {
+            for (total, bytes) in nodes.iter_mut().zip(&chunk.nodes) {
+                *total = total.saturating_sub(*bytes);
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum LeakStep {
+    Grow(usize),
+    Release,
+}
+
+// Each interval the profile decides how much to allocate:
+//   linear       leak_size_mb every time
+//   exponential  starts at leak_size_mb and multiplies by leak_growth_factor, one step capped at leak_peak_mb
+//   sawtooth     linear until leak_peak_mb is held, then everything is released
+//   bursts       1-4 steps at once on about a quarter of intervals, drawn from leak_seed
+//   step         one step, then nothing for leak_hold_intervals
+struct LeakSchedule {
+    profile: LeakProfile,
+    step_bytes: usize,
+    peak_bytes: usize,
+    growth_factor: f64,
+    hold_intervals: u64,
+    next_step: f64,
+    rng: u64,
+    interval: u64,
+}
+
+impl LeakSchedule {
+    fn new(config: &crate::config::Config) -> Self {
+        const SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+        let step_bytes = config.leak_size_mb.saturating_mul(1024 * 1024);
+        // xorshift never leaves 0, so the one seed that cancels the mix would never burst
+        let seed = match config.leak_seed ^ SEED_MIX {
+            0 => SEED_MIX,
+            mixed => mixed,
+        };
+        Self {
+            profile: config.leak_profile,
+            step_bytes,
+            peak_bytes: config.leak_peak_mb.saturating_mul(1024 * 1024),
+            growth_factor: config.leak_growth_factor,
+            hold_intervals: config.leak_hold_intervals,
+            next_step: step_bytes as f64,
+            rng: xorshift(seed),
+            interval: 0,
+        }
+    }
+
+    fn next(&mut self, held_bytes: usize) -> LeakStep {
+        let step = match self.profile {
+            LeakProfile::Linear => LeakStep::Grow(self.step_bytes),
+            LeakProfile::Exponential => {
+                let step = self.next_step.min(self.peak_bytes as f64) as usize;
+                self.next_step *= self.growth_factor;
+                LeakStep::Grow(step)
+            }
+            LeakProfile::Sawtooth if held_bytes >= self.peak_bytes => LeakStep::Release,
+            LeakProfile::Sawtooth => LeakStep::Grow(self.step_bytes),
+            LeakProfile::Bursts => {
+                self.rng = xorshift(self.rng);
+                let bytes = if self.rng.is_multiple_of(4) {
+                    self.step_bytes * (1 + (self.rng >> 8) as usize % 4)
+                } else { 0 };
+                LeakStep::Grow(bytes)
+            }
+            LeakProfile::Step => {
+                let bytes = if self.interval.is_multiple_of(self.hold_intervals + 1) { self.step_bytes } else { 0 };
+                LeakStep::Grow(bytes)
+            }
+        };
+        self.interval += 1;
+        step
+    }
+}
+
 pub fn spawn_leak_worker(running: Arc<AtomicBool>, config: crate::config::Config) {
-    let mut buf: Vec<Vec<u8>> = Vec::new();
-    let step_bytes = (config.leak_size_mb as usize).saturating_mul(1024 * 1024);
-    let sleep = Duration::from_secs(config.leak_interval_sec as u64);
+    let mut held: Vec<LeakChunk> = Vec::new();
+    let sleep = Duration::from_secs(config.leak_interval_sec);
     let ps = page_size();
+    let mut schedule = LeakSchedule::new(&config);
+    let max_leak_bytes = crate::guard::max_leak_bytes(&config);
+
+    while stress_may_continue(&running) {
+        let held_bytes: usize = held.iter().map(|c| c.data.len()).sum();
+        let grow = match schedule.next(held_bytes) {
+            LeakStep::Grow(bytes) => bytes,
+            LeakStep::Release => {
+                release_leak(&mut held);
+                0
+            }
+        };
+
+        // checked before allocating so the limit is never overshot, whatever the profile's step;
+        // the guard thread watches the same limit as a backstop
+        if let Some(limit) = max_leak_bytes.filter(|limit| (held_bytes + grow) as u64 > *limit) {
+            crate::guard::trip(format!("leak would hold {:.1} MB, limit {:.1} MB",
+                                       (held_bytes + grow) as f64 / (1024.0 * 1024.0),
+                                       limit as f64 / (1024.0 * 1024.0)),
+                               config.guard_action);
+            continue;
+        }
+
+        if grow > 0 {
+            let mut chunk = vec![0u8; grow];
 
-    while running.load(Ordering::SeqCst) {
-        let mut chunk = vec![0u8; step_bytes];
+            let len = chunk.len();
+            let mut i = 0usize;
+            while i < len {
+                chunk[i] = 1;
+                i = i.saturating_add(ps);
+            }
+            chunk[len - 1] = chunk[len - 1].wrapping_add(1);
+
+            LEAK_TOTAL_BYTES.fetch_add(grow as u64, Ordering::Relaxed);
+            let nodes = if config.leak_numa_report {
+                record_page_nodes(&chunk, ps)
+            } else { Vec::new() };
+            held.push(LeakChunk { data: chunk, nodes });
+        }
 
-        let len = chunk.len();
-        let mut i = 0usize;
-        while i < len {
-            chunk[i] = 1;
-            i = i.saturating_add(ps);
+        // slept in short steps so a guard release frees the memory promptly
+        let wake = Instant::now() + sleep;
+        while Instant::now() < wake && running.load(Ordering::SeqCst) && !stressors_released() {
+            thread::sleep((wake - Instant::now()).min(Duration::from_millis(200)));
         }
-        if len > 0 { chunk[len - 1] = chunk[len - 1].wrapping_add(1); }
+    }
+
+    if stressors_released() {
+        release_leak(&mut held);
+    }
+    std::hint::black_box(&held);
+}
+
+// move_pages(2) with no target nodes only reports where each page currently lives.
+// Returns this chunk's bytes per node after adding them to the running totals.
+fn record_page_nodes(chunk: &[u8], ps: usize) -> Vec<u64> {
+    let mut pages: Vec<*mut libc::c_void> = chunk.chunks(ps)
+        .map(|page| page.as_ptr() as *mut libc::c_void)
+        .collect();
+    let mut status = vec![0i32; pages.len()];
 
-        LEAK_TOTAL_BYTES.fetch_add(step_bytes as u64, Ordering::Relaxed);
-        buf.push(chunk);
+    let rc = unsafe {
+        libc::syscall(
+            libc::SYS_move_pages,
+            0,
+            pages.len() as libc::c_ulong,
+            pages.as_mut_ptr(),
+            std::ptr::null::<libc::c_int>(),
+            status.as_mut_ptr(),
+            0,
+        )
+    };
+    // ENOSYS on kernels without NUMA; the report then just has no per-node split
+    if rc != 0 { return Vec::new(); }
 
-        thread::sleep(sleep);
+    let mut chunk_nodes: Vec<u64> = Vec::new();
+    // negative status is an errno for that page, e.g. not yet faulted in
+    for node in status.into_iter().filter(|s| *s >= 0).map(|s| s as usize) {
+        if chunk_nodes.len() <= node {
+            chunk_nodes.resize(node + 1, 0);
+        }
+        chunk_nodes[node] += ps as u64;
+    }
+
+    if let Ok(mut nodes) = LEAK_NODE_BYTES.lock() {
+        if nodes.len() < chunk_nodes.len() {
+            nodes.resize(chunk_nodes.len(), 0);
+        }
+        for (total, bytes) in nodes.iter_mut().zip(&chunk_nodes) {
+            *total += bytes;
+        }
     }
+    chunk_nodes
+}
+
+fn thread_cpu_time() -> Duration {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) };
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
 
-    std::hint::black_box(&buf);
+fn xorshift(mut x: u64) -> u64 {
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    x
 }
 
-pub fn spawn_cpu_stress(running: Arc<AtomicBool>, _idx: usize) {
+// State for one worker's workload. Chunks are sized to take well under a millisecond
+// so the duty cycle below can stop close to its target.
+struct CpuWorkloadState {
+    kind: CpuWorkload,
+    rng: u64,
+    acc: u64,
+    fp: [f64; 4],
+    buf: Vec<u64>,
+    pos: usize,
+}
+
+impl CpuWorkloadState {
+    fn new(kind: CpuWorkload, working_set_kb: usize, seed: u64) -> Self {
+        let mut rng = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        let words = (working_set_kb.max(64) * 1024) / 8;
+
+        let buf = match kind {
+            CpuWorkload::Memory => vec![1u64; words],
+            CpuWorkload::Cache => {
+                // one slot per 64-byte line, linked into a single random cycle (Sattolo's shuffle)
+                // so every load depends on the previous one and the prefetcher can't guess the next
+                let lines = words / 8;
+                let mut order: Vec<usize> = (0..lines).collect();
+                for i in (1..lines).rev() {
+                    rng = xorshift(rng);
+                    order.swap(i, (rng % i as u64) as usize);
+                }
+                let mut buf = vec![0u64; lines * 8];
+                for (k, line) in order.iter().enumerate() {
+                    buf[line * 8] = (order[(k + 1) % lines] * 8) as u64;
+                }
+                buf
+            }
+            _ => Vec::new(),
+        };
+
+        CpuWorkloadState { kind, rng, acc: seed, fp: [0.5, 1.5, 2.5, 3.5], buf, pos: 0 }
+    }
+
+    // runs one chunk and returns how much work it did in the kind's unit
+    fn run_chunk(&mut self) -> u64 {
+        match self.kind {
+            CpuWorkload::Int => {
+                const ITERS: u64 = 25_000;
+                let mut x = self.acc;
+                for i in 0..ITERS {
+                    x = (x.wrapping_mul(0x9E37_79B9_7F4A_7C15).wrapping_add(i)) ^ (x >> 29);
+                }
+                self.acc = std::hint::black_box(x);
+                // multiply, add, shift and xor per iteration
+                ITERS * 4
+            }
+            CpuWorkload::Float => {
+                const ITERS: u64 = 25_000;
+                // four independent multiply-add chains; each converges towards 1.0 and stays normal
+                let [mut a, mut b, mut c, mut d] = self.fp;
+                for _ in 0..ITERS {
+                    a = a * 0.999_999 + 0.000_001;
+                    b = b * 0.999_998 + 0.000_002;
+                    c = c * 0.999_997 + 0.000_003;
+                    d = d * 0.999_996 + 0.000_004;
+                }
+                self.fp = std::hint::black_box([a, b, c, d]);
+                ITERS * 8
+            }
+            CpuWorkload::Memory => {
+                const WORDS: usize = 128 * 1024;
+                let end = (self.pos + WORDS).min(self.buf.len());
+                let mut sum = self.acc;
+                for v in &mut self.buf[self.pos..end] {
+                    sum = sum.wrapping_add(*v);
+                    *v = v.wrapping_add(1);
+                }
+                self.acc = std::hint::black_box(sum);
+                let bytes = ((end - self.pos) * 8 * 2) as u64;
+                self.pos = if end == self.buf.len() { 0 } else { end };
+                bytes
+            }
+            CpuWorkload::Cache => {
+                const ACCESSES: u64 = 10_000;
+                let mut idx = self.pos;
+                for _ in 0..ACCESSES {
+                    idx = self.buf[idx] as usize;
+                }
+                self.pos = std::hint::black_box(idx);
+                ACCESSES
+            }
+            CpuWorkload::Branch => {
+                const BRANCHES: u64 = 50_000;
+                let (mut rng, mut acc) = (self.rng, self.acc);
+                for _ in 0..BRANCHES {
+                    rng = xorshift(rng);
+                    // black_box in one arm keeps the compiler from turning this into a cmov
+                    if rng & 1 == 0 {
+                        acc = acc.wrapping_add(rng >> 7);
+                    } else {
+                        acc = std::hint::black_box(acc.rotate_left(5) ^ rng);
+                    }
+                }
+                self.rng = rng;
+                self.acc = acc;
+                BRANCHES
+            }
+        }
+    }
+}
+
+// Each period spins until the thread's CPU time since start catches up with its share of wall time,
+// then sleeps out the rest. Working from totals corrects overshoot and preemption in later periods.
+pub fn spawn_cpu_stress(
+    running: Arc<AtomicBool>,
+    idx: usize,
+    target_percent: u32,
+    workload: CpuWorkload,
+    working_set_kb: usize,
+) {
     CPU_ACTIVE_WORKERS.fetch_add(1, Ordering::Relaxed);
 
-    const CHUNK: u64 = 400_000_000;
-    while running.load(Ordering::SeqCst) {
-        let mut x: u64 = 0;
-        for _ in 0..CHUNK {
-            x = x.wrapping_add(1);
+    const PERIOD: Duration = Duration::from_millis(100);
+    let target_percent = target_percent.clamp(1, 100);
+    let share = target_percent as f64 / 100.0;
+    let mut state = CpuWorkloadState::new(workload, working_set_kb, idx as u64 + 1);
+    let mut ops: u64 = 0;
+    let started = Instant::now();
+    let cpu_started = thread_cpu_time();
+
+    while stress_may_continue(&running) {
+        let period_start = Instant::now();
+
+        while thread_cpu_time() - cpu_started < started.elapsed().mul_f64(share)
+            && period_start.elapsed() < PERIOD
+        {
+            let done = state.run_chunk();
+            ops += done;
+            CPU_TOTAL_OPS.fetch_add(done, Ordering::Relaxed);
+        }
+        if let Some(rest) = PERIOD.checked_sub(period_start.elapsed()) {
+            thread::sleep(rest);
+        }
+
+        let cpu_secs = (thread_cpu_time() - cpu_started).as_secs_f64();
+        let wall_secs = started.elapsed().as_secs_f64();
+        if let Ok(mut workers) = CPU_WORKER_STATS.lock() {
+            if workers.len() <= idx {
+                workers.resize(idx + 1, CpuWorkerStats::default());
+            }
+            workers[idx] = CpuWorkerStats {
+                worker: idx,
+                target_percent,
+                cpu_secs,
+                wall_secs,
+                achieved_percent: if wall_secs > 0.0 { cpu_secs / wall_secs * 100.0 } else { 0.0 },
+                workload,
+                ops,
+                ops_per_cpu_sec: if cpu_secs > 0.0 { ops as f64 / cpu_secs } else { 0.0 },
+            };
         }
-        std::hint::black_box(x);
-        CPU_TOTAL_CYCLES.fetch_add(CHUNK, Ordering::Relaxed);
     }
 
     CPU_ACTIVE_WORKERS.fetch_sub(1, Ordering::Relaxed);
 }
+
+pub fn spawn_disk_stress(running: Arc<AtomicBool>, config: crate::config::Config) {
+    const ALIGN: usize = 4096;
+    const FSYNC_EVERY_BYTES: u64 = 64 * 1024 * 1024;
+
+    // O_DIRECT needs block-aligned sizes, offsets and buffers
+    let block = (config.disk_block_kb.max(1) * 1024).div_ceil(ALIGN) * ALIGN;
+    let max_bytes = config.disk_max_mb.saturating_mul(1024 * 1024);
+    let blocks = (max_bytes / block as u64).max(1);
+    let path = Path::new(&config.disk_stress_dir)
+        .join(format!("sysmon_disk_stress_{}.dat", std::process::id()));
+
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create(true).truncate(true);
+    if config.disk_direct {
+        options.custom_flags(libc::O_DIRECT);
+    }
+    let file = match options.open(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("[ERROR] Disk stress: {}: {}", path.display(), e);
+            return;
+        }
+    };
+    if let Ok(mut files) = DISK_STRESS_FILES.lock() {
+        files.push(path.clone());
+    }
+
+    let mut storage = vec![0u8; 2 * block + ALIGN];
+    let start = storage.as_ptr().align_offset(ALIGN);
+    let (write_buf, read_buf) = storage[start..start + 2 * block].split_at_mut(block);
+    // non-zero content so compressing or deduplicating storage can't shortcut the writes
+    for (i, byte) in write_buf.iter_mut().enumerate() {
+        *byte = (i % 251) as u8 + 1;
+    }
+
+    let mut rng = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0x9e37_79b9_7f4a_7c15) | 1;
+    let mut offset = |i: u64| -> u64 {
+        let index = match config.disk_pattern {
+            DiskPattern::Sequential => i,
+            DiskPattern::Random => {
+                rng ^= rng << 13;
+                rng ^= rng >> 7;
+                rng ^= rng << 17;
+                rng % blocks
+            }
+        };
+        index * block as u64
+    };
+
+    // --disk-max-mb caps everything written, not each pass; the last pass is cut short at the cap
+    let mut written = 0u64;
+    'passes: while stress_may_continue(&running) && written < max_bytes {
+        let mut unsynced = 0u64;
+        for i in 0..blocks {
+            if !stress_may_continue(&running) { break 'passes; }
+            let last = i + 1 == blocks || written + block as u64 >= max_bytes;
+            if let Err(e) = file.write_all_at(write_buf, offset(i)) {
+                eprintln!("[ERROR] Disk stress write: {}: {}", path.display(), e);
+                break 'passes;
+            }
+            DISK_WRITE_BYTES.fetch_add(block as u64, Ordering::Relaxed);
+            written += block as u64;
+
+            unsynced += block as u64;
+            if unsynced >= FSYNC_EVERY_BYTES || last {
+                if file.sync_all().is_ok() {
+                    DISK_FSYNCS.fetch_add(1, Ordering::Relaxed);
+                }
+                unsynced = 0;
+            }
+            if last { break; }
+        }
+
+        for i in 0..blocks {
+            if !stress_may_continue(&running) { break 'passes; }
+            // random offsets can land past what has been written yet; those reads come back short
+            match file.read_at(read_buf, offset(i)) {
+                Ok(n) => { DISK_READ_BYTES.fetch_add(n as u64, Ordering::Relaxed); }
+                Err(e) => {
+                    eprintln!("[ERROR] Disk stress read: {}: {}", path.display(), e);
+                    break 'passes;
+                }
+            }
+        }
+    }
+
+    if written >= max_bytes {
+        println!("[DISK] Wrote the --disk-max-mb cap of {} MB, disk stress done", config.disk_max_mb);
+    }
+    drop(file);
+    remove_disk_stress_files();
+}
+
+pub fn remove_disk_stress_files() {
+    if let Ok(mut files) = DISK_STRESS_FILES.lock() {
+        for path in files.drain(..) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn disk_stress_stops_at_the_total_cap() {
+        let dir = std::env::temp_dir().join(format!("sysmon-disk-stress-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = crate::config::Config::parse_from([
+            "sysmon", "--disk-stress-dir", dir.to_str().unwrap(),
+            "--disk-max-mb", "1", "--disk-block-kb", "96", "--disk-pattern", "random",
+        ]);
+
+        let before = disk_write_bytes();
+        // returns on its own once the cap is written, with running still set
+        spawn_disk_stress(Arc::new(AtomicBool::new(true)), config);
+        let written = disk_write_bytes() - before;
+
+        // whole 96 KB blocks, so the cap is overshot by less than one
+        assert!((1024 * 1024..1024 * 1024 + 96 * 1024).contains(&written), "{}", written);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn cpu_worker_holds_its_duty_cycle() {
+        let running = Arc::new(AtomicBool::new(true));
+        let r = running.clone();
+        // an index of its own, the stats table is shared by every worker in the process
+        let worker = thread::spawn(move || spawn_cpu_stress(r, 5, 30, CpuWorkload::Int, 64));
+        thread::sleep(Duration::from_millis(800));
+        running.store(false, Ordering::SeqCst);
+        worker.join().unwrap();
+
+        let stats = cpu_worker_stats()[5].clone();
+        assert_eq!((stats.worker, stats.target_percent), (5, 30));
+        assert!(stats.wall_secs >= 0.7);
+        // never above the target beyond one chunk of work; below it only if the test box is starved
+        assert!(stats.achieved_percent > 0.0 && stats.achieved_percent <= 35.0, "{}", stats.achieved_percent);
+    }
+
+    const MB: usize = 1024 * 1024;
+
+    fn schedule(args: &[&str]) -> LeakSchedule {
+        let argv = ["sysmon", "--leak-size-mb", "10", "--leak-peak-mb", "35"].iter().chain(args);
+        LeakSchedule::new(&crate::config::Config::parse_from(argv))
+    }
+
+    fn grows(schedule: &mut LeakSchedule, intervals: usize) -> Vec<usize> {
+        (0..intervals)
+            .map(|_| match schedule.next(0) {
+                LeakStep::Grow(bytes) => bytes / MB,
+                LeakStep::Release => panic!("nothing held to release"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn linear_exponential_and_step_profiles() {
+        assert_eq!(grows(&mut schedule(&["--leak-profile", "linear"]), 3), [10, 10, 10]);
+        assert_eq!(grows(&mut schedule(&["--leak-profile", "exponential", "--leak-growth-factor", "1.5"]), 5),
+                   [10, 15, 22, 33, 35]);
+        assert_eq!(grows(&mut schedule(&["--leak-profile", "step", "--leak-hold-intervals", "2"]), 7),
+                   [10, 0, 0, 10, 0, 0, 10]);
+    }
+
+    #[test]
+    fn sawtooth_releases_at_the_peak() {
+        let mut s = schedule(&["--leak-profile", "sawtooth"]);
+        let steps: Vec<LeakStep> = [0, 10, 20, 30, 40, 0].iter().map(|held| s.next(held * MB)).collect();
+        assert_eq!(steps, [
+            LeakStep::Grow(10 * MB), LeakStep::Grow(10 * MB), LeakStep::Grow(10 * MB), LeakStep::Grow(10 * MB),
+            LeakStep::Release, LeakStep::Grow(10 * MB),
+        ]);
+    }
+
+    #[test]
+    fn bursts_follow_the_seed() {
+        let bursts = |seed: &str| grows(&mut schedule(&["--leak-profile", "bursts", "--leak-seed", seed]), 200);
+
+        let first = bursts("42");
+        assert_eq!(first, bursts("42"));
+        assert_ne!(first, bursts("43"));
+        assert!(first.iter().all(|mb| [0, 10, 20, 30, 40].contains(mb)));
+
+        // this seed cancels the mixing constant; it must still burst
+        let zero_state = bursts(&0x9E37_79B9_7F4A_7C15u64.to_string());
+        assert!(zero_state.iter().filter(|mb| **mb > 0).count() > 10, "{:?}", zero_state);
+    }
+}
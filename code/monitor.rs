@@ -1,6 +1,24 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use crate::cgroup::{self, CgroupMetrics};
+use crate::config::Config;
+use crate::disk::{self, DiskFilter, DiskMetrics};
 use crate::error::MonitorError;
+use crate::fd::{FdMetrics, FdTracker};
+use crate::process::{self, ProcessTable, ProcessTableOptions, SelfMetrics};
+use crate::filesystem::{self, FilesystemMetrics};
+use crate::leak_detect::{LeakAnalysis, LeakDetector};
+use crate::meminfo::{self, MeminfoMetrics};
+use crate::net::{self, InterfaceFilter, InterfaceMetrics};
+use crate::numa::{self, NumaNode};
+use crate::paths::{self, HostPaths};
+use crate::pressure::{self, PressureMetrics, PressureResource};
+use crate::sockets::{self, ProtocolMetrics};
+use crate::thermal::{self, ThermalMetrics};
+use crate::vmstat::{self, VmstatMetrics};
+use crate::watch::{self, ProcessTreeMetrics};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SystemMetrics {
@@ -10,6 +28,40 @@ pub struct SystemMetrics {
     pub swap: SwapMetrics,
     pub load: LoadMetrics,
     pub critical_events: Vec<CriticalEvent>,
+    #[serde(default)]
+    pub interval_secs: f64,
+    #[serde(default)]
+    pub pressure: Option<PressureMetrics>,
+    #[serde(default)]
+    pub vmstat: VmstatMetrics,
+    #[serde(default)]
+    pub disks: Vec<DiskMetrics>,
+    #[serde(default)]
+    pub network: Vec<InterfaceMetrics>,
+    #[serde(default)]
+    pub filesystems: Vec<FilesystemMetrics>,
+    #[serde(default)]
+    pub top_processes: Vec<ProcessTable>,
+    #[serde(default)]
+    pub self_process: SelfMetrics,
+    #[serde(default)]
+    pub watched: Option<ProcessTreeMetrics>,
+    #[serde(default)]
+    pub leak_analysis: Option<LeakAnalysis>,
+    #[serde(default)]
+    pub cgroup: Option<CgroupMetrics>,
+    #[serde(default)]
+    pub thermal: ThermalMetrics,
+    #[serde(default)]
+    pub meminfo: MeminfoMetrics,
+    #[serde(default)]
+    pub scheduler: SchedulerMetrics,
+    #[serde(default)]
+    pub protocols: Option<ProtocolMetrics>,
+    #[serde(default)]
+    pub numa_nodes: Vec<NumaNode>,
+    #[serde(default)]
+    pub fds: FdMetrics,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -21,6 +73,8 @@ pub struct MemoryMetrics {
     pub free_kb: u64,
     pub buffers_kb: u64,
     pub cached_kb: u64,
+    #[serde(default)]
+    pub cgroup_limited: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -31,6 +85,71 @@ pub struct CpuMetrics {
     pub iowait_time: u64,
     pub total_time: u64,
     pub cpu_usage_percent: f64,
+    #[serde(default)]
+    pub nice_time: u64,
+    #[serde(default)]
+    pub irq_time: u64,
+    #[serde(default)]
+    pub softirq_time: u64,
+    #[serde(default)]
+    pub steal_time: u64,
+    #[serde(default)]
+    pub guest_time: u64,
+    #[serde(default)]
+    pub guest_nice_time: u64,
+    #[serde(default)]
+    pub states: CpuStatePercent,
+    #[serde(default)]
+    pub cores: Vec<CoreMetrics>,
+    #[serde(default)]
+    pub cgroup_limited: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CpuStatePercent {
+    pub user: f64,
+    pub nice: f64,
+    pub system: f64,
+    pub idle: f64,
+    pub iowait: f64,
+    pub irq: f64,
+    pub softirq: f64,
+    pub steal: f64,
+    pub guest: f64,
+    pub guest_nice: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CoreMetrics {
+    pub core: usize,
+    pub user_time: u64,
+    pub nice_time: u64,
+    pub system_time: u64,
+    pub idle_time: u64,
+    pub iowait_time: u64,
+    pub irq_time: u64,
+    pub softirq_time: u64,
+    pub steal_time: u64,
+    pub guest_time: u64,
+    pub guest_nice_time: u64,
+    pub total_time: u64,
+    pub active_delta: u64,
+    pub total_delta: u64,
+    pub usage_percent: f64,
+    pub states: CpuStatePercent,
+}
+
+// ctxt, intr and processes are counters since boot; procs_running and procs_blocked are gauges
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SchedulerMetrics {
+    pub context_switches: u64,
+    pub interrupts: u64,
+    pub forks: u64,
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+    pub context_switches_per_sec: f64,
+    pub interrupts_per_sec: f64,
+    pub forks_per_sec: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -58,116 +177,438 @@ pub struct CriticalEvent {
     pub timestamp: chrono::DateTime<chrono::Local>,
 }
 
-pub fn collect_metrics() -> Result<SystemMetrics, MonitorError> {
-    let memory = collect_memory_metrics()?;
-    let cpu = collect_cpu_metrics()?;
-    let load = collect_load_metrics()?;
-    let swap = collect_swap_metrics()?;
-    let critical_events = detect_critical_events(&memory, &load, &swap);
-
-    Ok(SystemMetrics {
-        timestamp: chrono::Local::now(),
-        memory,
-        cpu,
-        swap,
-        load,
-        critical_events,
-    })
+#[derive(Debug, Default)]
+pub struct CounterSet {
+    prev: HashMap<String, (u64, u64)>,
+    generation: u64,
+    last_sample: Option<Instant>,
+    elapsed_secs: f64,
 }
 
-fn collect_memory_metrics() -> Result<MemoryMetrics, MonitorError> {
-    let content = fs::read_to_string("/proc/meminfo")
-        .map_err(|e| MonitorError::FileRead(format!("/proc/meminfo: {}", e)))?;
+impl CounterSet {
+    pub fn begin_sample(&mut self) {
+        let now = Instant::now();
+        self.elapsed_secs = self.last_sample
+            .map(|prev| now.duration_since(prev).as_secs_f64())
+            .unwrap_or(0.0);
+        self.last_sample = Some(now);
 
-    let mut total = 0u64;
-    let mut available = 0u64;
-    let mut free = 0u64;
-    let mut buffers = 0u64;
-    let mut cached = 0u64;
+        // forget counters that were not observed last sample (exited processes, removed devices)
+        let generation = self.generation;
+        self.prev.retain(|_, (_, seen)| *seen == generation);
+        self.generation += 1;
+    }
 
-    for line in content.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 2 { continue; }
-        let value = parts[1].parse::<u64>().unwrap_or(0);
+    pub fn elapsed_secs(&self) -> f64 {
+        self.elapsed_secs
+    }
 
-        match parts[0] {
-            "MemTotal:" => total = value,
-            "MemAvailable:" => available = value,
-            "MemFree:" => free = value,
-            "Buffers:" => buffers = value,
-            "Cached:" => cached = value,
-            _ => {}
+    pub fn has_baseline(&self, key: &str) -> bool {
+        self.prev.contains_key(key)
+    }
+
+    // None on the first sample of a key and when the counter went backwards (reset/wrap)
+    pub fn delta(&mut self, key: &str, value: u64) -> Option<u64> {
+        match self.prev.insert(key.to_string(), (value, self.generation)) {
+            Some((prev, _)) if value >= prev => Some(value - prev),
+            _ => None,
+        }
+    }
+
+    pub fn rate(&mut self, key: &str, value: u64) -> f64 {
+        match self.delta(key, value) {
+            Some(delta) => self.per_sec(delta),
+            None => 0.0,
+        }
+    }
+
+    pub fn per_sec(&self, delta: u64) -> f64 {
+        if self.elapsed_secs > 0.0 {
+            delta as f64 / self.elapsed_secs
+        } else { 0.0 }
+    }
+}
+
+#[derive(Debug)]
+pub struct Collector {
+    paths: HostPaths,
+    counters: CounterSet,
+    disk_filter: DiskFilter,
+    interface_filter: InterfaceFilter,
+    process_options: ProcessTableOptions,
+    watch_pid: Option<u32>,
+    leak_detector: LeakDetector,
+    fd_tracker: FdTracker,
+    cgroup_dir: Option<PathBuf>,
+}
+
+impl Collector {
+    pub fn new(config: &Config) -> Result<Self, MonitorError> {
+        let paths = HostPaths::new(&config.proc_root, &config.sys_root);
+        // only a cgroup asked for by name is fatal; failing to discover our own one just drops the section
+        let cgroup_dir = match cgroup::resolve_cgroup_dir(&paths, config.cgroup_path.as_deref()) {
+            Ok(dir) => dir,
+            Err(e) if config.cgroup_path.is_some() => return Err(e),
+            Err(e) => {
+                eprintln!("[WARN] cgroup unavailable: {}", e);
+                None
+            }
+        };
+
+        Ok(Self {
+            paths,
+            counters: CounterSet::default(),
+            disk_filter: DiskFilter {
+                include_partitions: config.disk_include_partitions,
+                include_virtual: config.disk_include_virtual,
+            },
+            interface_filter: InterfaceFilter {
+                include: config.net_include.clone(),
+                exclude: config.net_exclude.clone(),
+            },
+            process_options: ProcessTableOptions {
+                top_n: config.top_n,
+                sort_keys: config.top_sort.clone(),
+            },
+            watch_pid: None,
+            leak_detector: LeakDetector::default(),
+            fd_tracker: FdTracker::default(),
+            cgroup_dir,
+        })
+    }
+
+    pub fn cgroup_dir(&self) -> Option<&Path> {
+        self.cgroup_dir.as_deref()
+    }
+
+    pub fn watch(&mut self, pid: u32) {
+        self.watch_pid = Some(pid);
+    }
+
+    pub fn collect(&mut self) -> Result<SystemMetrics, MonitorError> {
+        self.counters.begin_sample();
+
+        let paths = &self.paths;
+        let meminfo = meminfo::collect_meminfo(paths)?;
+        let mut memory = collect_memory_metrics(&meminfo);
+        let stat = paths::read(&paths.proc("stat"))?;
+        let mut cpu = collect_cpu_metrics(&stat, &mut self.counters)?;
+        let scheduler = collect_scheduler_metrics(&stat, &mut self.counters);
+        let cgroup = self.cgroup_dir.as_deref()
+            .map(|dir| cgroup::collect_cgroup_metrics(dir, &mut self.counters));
+        if let Some(cg) = &cgroup {
+            cg.apply_limits(&mut memory, &mut cpu);
+        }
+        let load = collect_load_metrics(paths)?;
+        let swap = collect_swap_metrics(&meminfo);
+        let numa_nodes = numa::collect_numa_metrics(paths, &meminfo, &mut self.counters);
+        let pressure = optional("pressure", pressure::collect_pressure_metrics(paths, &mut self.counters))
+            .flatten();
+        let vmstat = optional("vmstat", vmstat::collect_vmstat_metrics(paths, &mut self.counters))
+            .unwrap_or_default();
+        let disks = optional("diskstats", disk::collect_disk_metrics(paths, self.disk_filter, &mut self.counters))
+            .unwrap_or_default();
+        let network = optional("net/dev", net::collect_net_metrics(paths, &self.interface_filter, &mut self.counters))
+            .unwrap_or_default();
+        let protocols = optional("net/snmp", sockets::collect_protocol_metrics(paths, &mut self.counters));
+        let filesystems = optional("mounts", filesystem::collect_filesystem_metrics(paths))
+            .unwrap_or_default();
+        let thermal = thermal::collect_thermal_metrics(paths);
+        let top_processes = optional("process tables",
+                                     process::collect_process_tables(paths, &self.process_options, &mut self.counters))
+            .unwrap_or_default();
+        let fds = optional("sys/fs/file-nr", self.fd_tracker.collect(paths, &top_processes))
+            .unwrap_or_default();
+        let self_process = optional("self metrics", process::collect_self_metrics(&mut self.counters))
+            .unwrap_or_default();
+        let watched = match self.watch_pid {
+            Some(pid) => optional("watched process", watch::collect_tree_metrics(paths, pid, &mut self.counters))
+                .flatten(),
+            None => None,
+        };
+        // without a watched target the monitor's own leak worker is the process under test
+        let leak_analysis = match self.watch_pid {
+            Some(pid) => self.leak_detector.sample(paths, pid),
+            None => self.leak_detector.sample(&HostPaths::default(), std::process::id()),
+        };
+
+        let mut metrics = SystemMetrics {
+            timestamp: chrono::Local::now(),
+            memory,
+            cpu,
+            swap,
+            load,
+            critical_events: Vec::new(),
+            interval_secs: self.counters.elapsed_secs(),
+            pressure,
+            vmstat,
+            disks,
+            network,
+            filesystems,
+            top_processes,
+            self_process,
+            watched,
+            leak_analysis,
+            cgroup,
+            thermal,
+            meminfo,
+            scheduler,
+            protocols,
+            numa_nodes,
+            fds,
+        };
+        metrics.critical_events = detect_critical_events(&metrics);
+
+        Ok(metrics)
+    }
+}
+
+// Memory and swap exactly as a full sample reports them, without the other collectors
+pub fn collect_memory_and_swap(
+    paths: &HostPaths,
+    cgroup_dir: Option<&Path>,
+    counters: &mut CounterSet,
+) -> Result<(MemoryMetrics, SwapMetrics), MonitorError> {
+    let meminfo = meminfo::collect_meminfo(paths)?;
+    let mut memory = collect_memory_metrics(&meminfo);
+    if let Some(dir) = cgroup_dir {
+        cgroup::collect_cgroup_metrics(dir, counters).apply_memory_limit(&mut memory);
+    }
+    Ok((memory, collect_swap_metrics(&meminfo)))
+}
+
+#[cfg(test)]
+impl Collector {
+    pub fn for_fixture(fixture: &crate::paths::Fixture) -> Self {
+        use clap::Parser;
+        let root = |dir: &str| fixture.root.join(dir).display().to_string();
+        Self::new(&Config::parse_from(["sysmon", "--proc-root", &root("proc"), "--sys-root", &root("sys")])).unwrap()
+    }
+}
+
+// Sections restricted containers or a sparse --proc-root may not provide: a failure is reported
+// and leaves that section empty instead of discarding the whole sample
+fn optional<T>(section: &str, result: Result<T, MonitorError>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("[WARN] {} unavailable: {}", section, e);
+            None
         }
     }
+}
 
-    let used = total.saturating_sub(available);
+fn collect_memory_metrics(meminfo: &MeminfoMetrics) -> MemoryMetrics {
+    let total = meminfo.mem_total_kb;
+    let used = total.saturating_sub(meminfo.mem_available_kb);
     let used_percent = if total > 0 {
         (used as f64 / total as f64) * 100.0
     } else { 0.0 };
 
-    Ok(MemoryMetrics {
+    MemoryMetrics {
         total_kb: total,
-        available_kb: available,
+        available_kb: meminfo.mem_available_kb,
         used_kb: used,
         used_percent,
-        free_kb: free,
-        buffers_kb: buffers,
-        cached_kb: cached,
-    })
+        free_kb: meminfo.mem_free_kb,
+        buffers_kb: meminfo.buffers_kb,
+        cached_kb: meminfo.cached_kb,
+        cgroup_limited: false,
+    }
 }
 
-static mut PREV_CPU_STATS: Option<(u64, u64, u64, u64)> = None;
+#[derive(Default)]
+struct CpuTimes {
+    user: u64,
+    nice: u64,
+    system: u64,
+    idle: u64,
+    iowait: u64,
+    irq: u64,
+    softirq: u64,
+    steal: u64,
+    guest: u64,
+    guest_nice: u64,
+}
 
-fn collect_cpu_metrics() -> Result<CpuMetrics, MonitorError> {
-    let content = fs::read_to_string("/proc/stat")
-        .map_err(|e| MonitorError::FileRead(format!("/proc/stat: {}", e)))?;
+impl CpuTimes {
+    // guest and guest_nice are already accounted in user and nice
+    fn total(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle
+            + self.iowait + self.irq + self.softirq + self.steal
+    }
 
-    let line = content.lines().next()
-        .ok_or_else(|| MonitorError::ParseError("Empty /proc/stat".into()))?;
+    fn idle_total(&self) -> u64 {
+        self.idle + self.iowait
+    }
 
-    let parts: Vec<&str> = line.split_whitespace().collect();
+    fn delta(&self, counters: &mut CounterSet, prefix: &str) -> CpuTimes {
+        let mut d = |field: &str, value: u64| {
+            counters.delta(&format!("{}.{}", prefix, field), value).unwrap_or(0)
+        };
+        CpuTimes {
+            user: d("user", self.user),
+            nice: d("nice", self.nice),
+            system: d("system", self.system),
+            idle: d("idle", self.idle),
+            iowait: d("iowait", self.iowait),
+            irq: d("irq", self.irq),
+            softirq: d("softirq", self.softirq),
+            steal: d("steal", self.steal),
+            guest: d("guest", self.guest),
+            guest_nice: d("guest_nice", self.guest_nice),
+        }
+    }
+
+    fn percentages(&self) -> CpuStatePercent {
+        let total = self.total();
+        if total == 0 {
+            return CpuStatePercent::default();
+        }
+        let pct = |v: u64| (v as f64 / total as f64) * 100.0;
+        CpuStatePercent {
+            user: pct(self.user),
+            nice: pct(self.nice),
+            system: pct(self.system),
+            idle: pct(self.idle),
+            iowait: pct(self.iowait),
+            irq: pct(self.irq),
+            softirq: pct(self.softirq),
+            steal: pct(self.steal),
+            guest: pct(self.guest),
+            guest_nice: pct(self.guest_nice),
+        }
+    }
+}
+
+fn parse_cpu_line(parts: &[&str]) -> Result<CpuTimes, MonitorError> {
     if parts.len() < 5 {
         return Err(MonitorError::ParseError("Invalid /proc/stat format".into()));
     }
 
-    let user: u64 = parts[1].parse().unwrap_or(0);
-    let system: u64 = parts[3].parse().unwrap_or(0);
-    let idle: u64 = parts[4].parse().unwrap_or(0);
-    let iowait: u64 = parts.get(5).and_then(|s| s.parse().ok()).unwrap_or(0);
+    let field = |i: usize| parts.get(i).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+
+    Ok(CpuTimes {
+        user: field(1),
+        nice: field(2),
+        system: field(3),
+        idle: field(4),
+        iowait: field(5),
+        irq: field(6),
+        softirq: field(7),
+        steal: field(8),
+        guest: field(9),
+        guest_nice: field(10),
+    })
+}
+
+fn usage_from_delta(delta: &CpuTimes) -> (u64, u64, f64) {
+    let total_delta = delta.total();
+    let active_delta = total_delta.saturating_sub(delta.idle_total());
+    let usage_percent = if total_delta > 0 {
+        (active_delta as f64 / total_delta as f64) * 100.0
+    } else { 0.0 };
+    (active_delta, total_delta, usage_percent)
+}
 
-    let total: u64 = parts[1..].iter()
-        .filter_map(|s| s.parse::<u64>().ok())
-        .sum::<u64>();
+fn collect_cpu_metrics(content: &str, counters: &mut CounterSet) -> Result<CpuMetrics, MonitorError> {
+    let line = content.lines().next()
+        .ok_or_else(|| MonitorError::ParseError("Empty /proc/stat".into()))?;
 
-    let cpu_usage_percent = unsafe {
-        if let Some((prev_user, prev_system, prev_idle, prev_total)) = PREV_CPU_STATS {
-            let user_delta = user.saturating_sub(prev_user);
-            let system_delta = system.saturating_sub(prev_system);
-            let idle_delta = idle.saturating_sub(prev_idle);
-            let total_delta = total.saturating_sub(prev_total);
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    let times = parse_cpu_line(&parts)?;
 
-            if total_delta > 0 {
-                let active = user_delta + system_delta;
-                (active as f64 / total_delta as f64) * 100.0
-            } else { 0.0 }
-        } else { 0.0 }
-    };
+    let delta = times.delta(counters, "cpu");
+    let (_, _, cpu_usage_percent) = usage_from_delta(&delta);
 
-    unsafe { PREV_CPU_STATS = Some((user, system, idle, total)); }
+    let cores = collect_core_metrics(content, counters)?;
 
     Ok(CpuMetrics {
-        user_time: user,
-        system_time: system,
-        idle_time: idle,
-        iowait_time: iowait,
-        total_time: total,
+        user_time: times.user,
+        system_time: times.system,
+        idle_time: times.idle,
+        iowait_time: times.iowait,
+        total_time: times.total(),
         cpu_usage_percent,
+        nice_time: times.nice,
+        irq_time: times.irq,
+        softirq_time: times.softirq,
+        steal_time: times.steal,
+        guest_time: times.guest,
+        guest_nice_time: times.guest_nice,
+        states: delta.percentages(),
+        cores,
+        cgroup_limited: false,
     })
 }
 
-fn collect_load_metrics() -> Result<LoadMetrics, MonitorError> {
-    let content = fs::read_to_string("/proc/loadavg")
-        .map_err(|e| MonitorError::FileRead(format!("/proc/loadavg: {}", e)))?;
+fn collect_scheduler_metrics(content: &str, counters: &mut CounterSet) -> SchedulerMetrics {
+    let mut m = SchedulerMetrics::default();
+
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 2 { continue; }
+        // intr is followed by per-IRQ counts; its first value is the total
+        let value = parts[1].parse::<u64>().unwrap_or(0);
+
+        match parts[0] {
+            "ctxt" => m.context_switches = value,
+            "intr" => m.interrupts = value,
+            "processes" => m.forks = value,
+            "procs_running" => m.procs_running = value,
+            "procs_blocked" => m.procs_blocked = value,
+            _ => {}
+        }
+    }
+
+    m.context_switches_per_sec = counters.rate("sched.ctxt", m.context_switches);
+    m.interrupts_per_sec = counters.rate("sched.intr", m.interrupts);
+    m.forks_per_sec = counters.rate("sched.forks", m.forks);
+    m
+}
+
+fn collect_core_metrics(content: &str, counters: &mut CounterSet) -> Result<Vec<CoreMetrics>, MonitorError> {
+    let mut cores = Vec::new();
+
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let core = match parts.first()
+            .and_then(|p| p.strip_prefix("cpu"))
+            .and_then(|n| n.parse::<usize>().ok())
+        {
+            Some(core) => core,
+            None => continue,
+        };
+
+        let times = parse_cpu_line(&parts)?;
+        let delta = times.delta(counters, parts[0]);
+        let (active_delta, total_delta, usage_percent) = usage_from_delta(&delta);
+
+        cores.push(CoreMetrics {
+            core,
+            user_time: times.user,
+            nice_time: times.nice,
+            system_time: times.system,
+            idle_time: times.idle,
+            iowait_time: times.iowait,
+            irq_time: times.irq,
+            softirq_time: times.softirq,
+            steal_time: times.steal,
+            guest_time: times.guest,
+            guest_nice_time: times.guest_nice,
+            total_time: times.total(),
+            active_delta,
+            total_delta,
+            usage_percent,
+            states: delta.percentages(),
+        });
+    }
+
+    Ok(cores)
+}
+
+fn collect_load_metrics(paths: &HostPaths) -> Result<LoadMetrics, MonitorError> {
+    let content = paths::read(&paths.proc("loadavg"))?;
 
     let parts: Vec<&str> = content.split_whitespace().collect();
     if parts.len() < 3 {
@@ -181,7 +622,7 @@ fn collect_load_metrics() -> Result<LoadMetrics, MonitorError> {
     let load_15 = parts[2].parse::<f64>()
         .map_err(|e| MonitorError::ParseError(format!("Load average: {}", e)))?;
 
-    let cpu_count = num_cpus();
+    let cpu_count = num_cpus(paths);
     let load_percent_1min = if cpu_count > 0 {
         (load_1 / cpu_count as f64) * 100.0
     } else { 0.0 };
@@ -195,50 +636,31 @@ fn collect_load_metrics() -> Result<LoadMetrics, MonitorError> {
     })
 }
 
-fn num_cpus() -> usize {
-    fs::read_to_string("/proc/cpuinfo")
+fn num_cpus(paths: &HostPaths) -> usize {
+    paths::read(&paths.proc("cpuinfo"))
         .ok()
         .map(|content| content.lines().filter(|l| l.starts_with("processor")).count())
         .unwrap_or(1)
 }
 
-fn collect_swap_metrics() -> Result<SwapMetrics, MonitorError> {
-    let content = fs::read_to_string("/proc/meminfo")
-        .map_err(|e| MonitorError::FileRead(format!("/proc/meminfo: {}", e)))?;
-
-    let mut total = 0u64;
-    let mut free = 0u64;
-
-    for line in content.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 2 { continue; }
-        let value = parts[1].parse::<u64>().unwrap_or(0);
-
-        match parts[0] {
-            "SwapTotal:" => total = value,
-            "SwapFree:" => free = value,
-            _ => {}
-        }
-    }
-
+fn collect_swap_metrics(meminfo: &MeminfoMetrics) -> SwapMetrics {
+    let total = meminfo.swap_total_kb;
+    let free = meminfo.swap_free_kb;
     let used = total.saturating_sub(free);
     let used_percent = if total > 0 {
         (used as f64 / total as f64) * 100.0
     } else { 0.0 };
 
-    Ok(SwapMetrics {
+    SwapMetrics {
         total_kb: total,
         used_kb: used,
         free_kb: free,
         used_percent,
-    })
+    }
 }
 
-fn detect_critical_events(
-    memory: &MemoryMetrics,
-    load: &LoadMetrics,
-    swap: &SwapMetrics,
-) -> Vec<CriticalEvent> {
+fn detect_critical_events(metrics: &SystemMetrics) -> Vec<CriticalEvent> {
+    let SystemMetrics { memory, cpu, load, swap, vmstat, scheduler, .. } = metrics;
     let mut events = Vec::new();
     let now = chrono::Local::now();
 
@@ -262,16 +684,40 @@ fn detect_critical_events(
         events.push(CriticalEvent {
             event_type: "LOAD_CRITICAL".into(),
             severity: "CRITICAL".into(),
-            description: format!("Load at {:.1}% (avg: {:.2})", 
-                                 load.load_percent_1min, load.load_average_1min),
+            description: format!("Load at {:.1}% (avg: {:.2}; {} running, {} blocked)", 
+                                 load.load_percent_1min, load.load_average_1min,
+                                 scheduler.procs_running, scheduler.procs_blocked),
             timestamp: now,
         });
     } else if load.load_percent_1min > 150.0 {
         events.push(CriticalEvent {
             event_type: "LOAD_WARNING".into(),
             severity: "HIGH".into(),
-            description: format!("Load at {:.1}% (avg: {:.2})", 
-                                 load.load_percent_1min, load.load_average_1min),
+            description: format!("Load at {:.1}% (avg: {:.2}; {} running, {} blocked)", 
+                                 load.load_percent_1min, load.load_average_1min,
+                                 scheduler.procs_running, scheduler.procs_blocked),
+            timestamp: now,
+        });
+    }
+
+    // load counts D-state tasks too, so many blocked tasks with idle CPUs is an I/O pileup,
+    // not CPU saturation
+    if scheduler.procs_blocked as usize >= load.cpu_count.max(4) {
+        events.push(CriticalEvent {
+            event_type: "BLOCKED_TASKS".into(),
+            severity: "HIGH".into(),
+            description: format!("{} tasks in uninterruptible sleep ({} running) with CPU at {:.1}% and iowait {:.1}%",
+                                 scheduler.procs_blocked, scheduler.procs_running,
+                                 cpu.cpu_usage_percent, cpu.states.iowait),
+            timestamp: now,
+        });
+    }
+
+    if cpu.states.steal > 10.0 {
+        events.push(CriticalEvent {
+            event_type: "STEAL_WARNING".into(),
+            severity: "HIGH".into(),
+            description: format!("CPU steal time at {:.1}% (hypervisor contention)", cpu.states.steal),
             timestamp: now,
         });
     }
@@ -292,5 +738,374 @@ fn detect_critical_events(
         });
     }
 
+    if vmstat.oom_kill_delta > 0 {
+        events.push(CriticalEvent {
+            event_type: "OOM_KILL".into(),
+            severity: "CRITICAL".into(),
+            description: format!("OOM killer fired {} time(s) since last sample", vmstat.oom_kill_delta),
+            timestamp: now,
+        });
+    }
+
+    // under strict accounting this is where allocations start failing; otherwise it's how far
+    // the leak has overcommitted the box
+    let mi = &metrics.meminfo;
+    if mi.commit_limit_kb > 0 && mi.committed_percent > 90.0 {
+        events.push(CriticalEvent {
+            event_type: "OVERCOMMIT_WARNING".into(),
+            severity: if mi.commit_enforced() { "CRITICAL" } else { "HIGH" }.into(),
+            description: format!("Committed_AS at {:.1}% of CommitLimit ({} / {} MB, overcommit_memory={})",
+                                 mi.committed_percent,
+                                 mi.committed_as_kb / 1024,
+                                 mi.commit_limit_kb / 1024,
+                                 mi.overcommit_mode.map(|m| m.to_string()).unwrap_or_else(|| "?".into())),
+            timestamp: now,
+        });
+    }
+
+    let swap_pages_per_sec = vmstat.pswpin_per_sec + vmstat.pswpout_per_sec;
+    if swap_pages_per_sec > 1000.0 {
+        events.push(CriticalEvent {
+            event_type: "SWAP_THRASHING".into(),
+            severity: "HIGH".into(),
+            description: format!("Swapping {:.0} pages/s (in {:.0}/s, out {:.0}/s)",
+                                 swap_pages_per_sec, vmstat.pswpin_per_sec, vmstat.pswpout_per_sec),
+            timestamp: now,
+        });
+    }
+
+    if let Some(cg) = &metrics.cgroup {
+        if let Some(events_cg) = cg.memory_events.as_ref().filter(|e| e.oom_kill_delta > 0) {
+            events.push(CriticalEvent {
+                event_type: "CGROUP_OOM_KILL".into(),
+                severity: "CRITICAL".into(),
+                description: format!("{}: {} OOM kill(s) at memory.max since last sample",
+                                     cg.path, events_cg.oom_kill_delta),
+                timestamp: now,
+            });
+        }
+        if let Some(cpu_cg) = cg.cpu.as_ref().filter(|c| c.throttled_percent > 20.0) {
+            events.push(CriticalEvent {
+                event_type: "CPU_THROTTLED".into(),
+                severity: "HIGH".into(),
+                description: format!("{}: throttled in {:.1}% of CFS periods (limit {:.2} cores)",
+                                     cg.path, cpu_cg.throttled_percent, cpu_cg.limit_cores.unwrap_or(0.0)),
+                timestamp: now,
+            });
+        }
+    }
+
+    if let Some(leak) = metrics.leak_analysis.as_ref().filter(|l| l.suspected) {
+        events.push(CriticalEvent {
+            event_type: "LEAK_SUSPECTED".into(),
+            severity: "HIGH".into(),
+            description: format!("PID {} private memory growing {:.1} MB/hour over {:.0}s (R² {:.2}; anon {:+.1} MB, swap {:+.1} MB)",
+                                 leak.pid, leak.leak_mb_per_hour, leak.window_secs, leak.r_squared,
+                                 leak.anon_growth_kb as f64 / 1024.0,
+                                 leak.swap_growth_kb as f64 / 1024.0),
+            timestamp: now,
+        });
+    }
+
+    // one event per sample, for the hottest sensor within 10°C of where the hardware starts
+    // protecting itself, or plain hot
+    let hottest = metrics.thermal.readings().into_iter()
+        .filter(|(_, temp_c, limit_c)| limit_c.is_some_and(|limit| *temp_c >= limit - 10.0) || *temp_c >= 90.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+    if let Some((sensor, temp_c, limit_c)) = hottest {
+        let limit = limit_c.map(|l| format!(" (limit {:.0}°C)", l)).unwrap_or_default();
+        let freq = metrics.thermal.lowest_frequency()
+            .map(|f| format!("; slowest core {} at {:.0} MHz, {:.0}% of max", f.core, f.current_mhz, f.percent_of_max))
+            .unwrap_or_default();
+        events.push(CriticalEvent {
+            event_type: "THERMAL_WARNING".into(),
+            severity: "HIGH".into(),
+            description: format!("{} at {:.1}°C{}{}", sensor, temp_c, limit, freq),
+            timestamp: now,
+        });
+    }
+
+    let fds = &metrics.fds;
+    if fds.used_percent > 80.0 {
+        events.push(CriticalEvent {
+            event_type: if fds.used_percent > 95.0 { "FD_CRITICAL" } else { "FD_WARNING" }.into(),
+            severity: if fds.used_percent > 95.0 { "CRITICAL" } else { "HIGH" }.into(),
+            description: format!("System-wide file handles at {:.1}% ({} of file-max {})",
+                                 fds.used_percent, fds.allocated, fds.max),
+            timestamp: now,
+        });
+    }
+
+    for p in &fds.processes {
+        let percent = p.used_percent.unwrap_or(0.0);
+        let (event_type, severity) = if percent > 95.0 {
+            ("FD_CRITICAL", "CRITICAL")
+        } else if percent > 80.0 || p.growing {
+            ("FD_WARNING", "HIGH")
+        } else { continue };
+
+        let limit = p.soft_limit.map(|l| l.to_string()).unwrap_or_else(|| "unlimited".into());
+        events.push(CriticalEvent {
+            event_type: event_type.into(),
+            severity: severity.into(),
+            description: format!("PID {} ({}): {} open fds of RLIMIT_NOFILE {} ({:.1}%){}",
+                                 p.pid, p.comm, p.fd_count, limit, percent,
+                                 if p.growing { format!(", grew {:+} steadily", p.growth) } else { String::new() }),
+            timestamp: now,
+        });
+    }
+
+    // read-only images (squashfs snaps, ISOs) are always full and can't get any fuller
+    for fs in metrics.filesystems.iter().filter(|fs| !fs.read_only) {
+        let worst = fs.used_percent.max(fs.inodes_used_percent);
+        let (event_type, severity) = if worst > 95.0 {
+            ("FS_CRITICAL", "CRITICAL")
+        } else if worst > 85.0 {
+            ("FS_WARNING", "HIGH")
+        } else { continue };
+
+        events.push(CriticalEvent {
+            event_type: event_type.into(),
+            severity: severity.into(),
+            description: format!("{} ({}): space {:.1}% used, inodes {:.1}% used",
+                                 fs.mount_point, fs.device, fs.used_percent, fs.inodes_used_percent),
+            timestamp: now,
+        });
+    }
+
+    for iface in &metrics.network {
+        if iface.errors_per_sec > 1.0 {
+            events.push(CriticalEvent {
+                event_type: "NET_ERRORS".into(),
+                severity: "HIGH".into(),
+                description: format!("{}: {:.1} errors/s (rx {} tx {} total)",
+                                     iface.interface, iface.errors_per_sec, iface.rx_errors, iface.tx_errors),
+                timestamp: now,
+            });
+        }
+        if iface.drops_per_sec > 100.0 {
+            events.push(CriticalEvent {
+                event_type: "NET_DROPS".into(),
+                severity: "HIGH".into(),
+                description: format!("{}: {:.1} drops/s (rx {} tx {} total)",
+                                     iface.interface, iface.drops_per_sec, iface.rx_drops, iface.tx_drops),
+                timestamp: now,
+            });
+        }
+    }
+
+    if let Some(pressure) = &metrics.pressure {
+        if let Some(mem) = &pressure.memory {
+            push_pressure_event(&mut events, "MEMORY", mem, 10.0, 10.0, now);
+        }
+        if let Some(io) = &pressure.io {
+            push_pressure_event(&mut events, "IO", io, 30.0, 20.0, now);
+        }
+        if let Some(cpu) = &pressure.cpu {
+            push_pressure_event(&mut events, "CPU", cpu, 40.0, 80.0, now);
+        }
+    }
+
     events
 }
+
+// Critical is judged on "full" stalls; system-wide cpu "full" is always zero, so cpu uses "some"
+fn push_pressure_event(
+    events: &mut Vec<CriticalEvent>,
+    resource: &str,
+    psi: &PressureResource,
+    some_warning: f64,
+    full_critical: f64,
+    now: chrono::DateTime<chrono::Local>,
+) {
+    let full_avg10 = psi.full.as_ref()
+        .filter(|_| resource != "CPU")
+        .map(|full| full.avg10)
+        .unwrap_or(psi.some.avg10);
+
+    if full_avg10 > full_critical {
+        events.push(CriticalEvent {
+            event_type: format!("{}_PRESSURE_CRITICAL", resource),
+            severity: "CRITICAL".into(),
+            description: format!("{} pressure: full avg10 {:.1}% (some {:.1}%)",
+                                 resource, full_avg10, psi.some.avg10),
+            timestamp: now,
+        });
+    } else if psi.some.avg10 > some_warning {
+        events.push(CriticalEvent {
+            event_type: format!("{}_PRESSURE_WARNING", resource),
+            severity: "HIGH".into(),
+            description: format!("{} pressure: some avg10 {:.1}% (avg60 {:.1}%)",
+                                 resource, psi.some.avg10, psi.some.avg60),
+            timestamp: now,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::Fixture;
+
+    // just what a sample can't do without
+    const MINIMAL_PROC: &[(&str, &str)] = &[
+        ("proc/meminfo", "MemTotal: 1000 kB\nMemFree: 400 kB\nMemAvailable: 600 kB\nBuffers: 0 kB\n\
+                          Cached: 100 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n"),
+        ("proc/stat", "cpu  100 0 50 800 0 0 0 0 0 0\ncpu0 100 0 50 800 0 0 0 0 0 0\n"),
+        ("proc/loadavg", "0.50 0.40 0.30 1/100 42\n"),
+    ];
+
+    fn fields(line: &str) -> Vec<&str> {
+        line.split_whitespace().collect()
+    }
+
+    #[test]
+    fn counter_delta_needs_a_baseline_and_rejects_resets() {
+        let mut counters = CounterSet::default();
+        assert_eq!(counters.delta("a", 100), None);
+        assert_eq!(counters.delta("a", 150), Some(50));
+        assert_eq!(counters.delta("a", 150), Some(0));
+        // went backwards: no delta, but the new value is the next baseline
+        assert_eq!(counters.delta("a", 10), None);
+        assert_eq!(counters.delta("a", 25), Some(15));
+        // keys are independent
+        assert_eq!(counters.delta("b", 25), None);
+    }
+
+    #[test]
+    fn counters_missing_from_a_sample_are_forgotten() {
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+        counters.delta("proc.1.cpu", 10);
+        counters.delta("proc.2.cpu", 10);
+
+        // pid 2 exited before this sample
+        counters.begin_sample();
+        assert_eq!(counters.delta("proc.1.cpu", 15), Some(5));
+        assert!(counters.has_baseline("proc.2.cpu"));
+
+        counters.begin_sample();
+        assert!(counters.has_baseline("proc.1.cpu"));
+        assert!(!counters.has_baseline("proc.2.cpu"));
+    }
+
+    #[test]
+    fn rate_is_per_second_of_the_sample_interval() {
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+        assert_eq!(counters.per_sec(100), 0.0);
+        assert_eq!(counters.rate("a", 100), 0.0);
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        counters.begin_sample();
+        let expected = 400.0 / counters.elapsed_secs();
+        assert!((counters.rate("a", 500) - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cpu_line_total_sums_every_column() {
+        let times = parse_cpu_line(&fields("cpu  100 5 50 800 20 3 2 1 0 0")).unwrap();
+        assert_eq!((times.user, times.system, times.idle, times.iowait), (100, 50, 800, 20));
+        assert_eq!(times.total(), 981);
+
+        assert!(parse_cpu_line(&fields("cpu 1 2 3")).is_err());
+    }
+
+    #[test]
+    fn guest_time_is_not_counted_twice() {
+        // guest 40 and guest_nice 10 are already part of user and nice
+        let times = parse_cpu_line(&fields("cpu  140 10 50 700 20 30 20 30 40 10")).unwrap();
+        assert_eq!(times.total(), 1000);
+        assert_eq!(times.idle_total(), 720);
+
+        let states = times.percentages();
+        assert!((states.user - 14.0).abs() < 1e-9);
+        assert_eq!((states.nice, states.steal, states.guest), (1.0, 3.0, 4.0));
+        let sum = states.user + states.nice + states.system + states.idle + states.iowait
+            + states.irq + states.softirq + states.steal;
+        assert!((sum - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn core_usage_comes_from_deltas_between_samples() {
+        let first = "cpu  200 0 100 1700 0\ncpu0 100 0 50 850 0\ncpu1 100 0 50 850 0\nintr 12345\n";
+        let mut counters = CounterSet::default();
+        let cores = collect_core_metrics(first, &mut counters).unwrap();
+        assert_eq!(cores.len(), 2);
+        // no previous sample yet
+        assert!(cores.iter().all(|c| c.total_delta == 0 && c.usage_percent == 0.0));
+
+        // cpu0 was busy for 75 of 100 ticks, cpu1 idle throughout
+        let second = "cpu  275 0 100 1825 0\ncpu0 150 0 75 875 0\ncpu1 100 0 50 950 0\n";
+        let cores = collect_core_metrics(second, &mut counters).unwrap();
+        assert_eq!((cores[0].core, cores[0].active_delta, cores[0].total_delta), (0, 75, 100));
+        assert_eq!(cores[0].usage_percent, 75.0);
+        assert_eq!((cores[1].core, cores[1].usage_percent), (1, 0.0));
+    }
+
+    #[test]
+    fn sparse_proc_root_still_gives_a_sample() {
+        // no pressure/, vmstat, diskstats, net/dev, mounts or processes
+        let fixture = Fixture::new("monitor-sparse", MINIMAL_PROC);
+        let metrics = Collector::for_fixture(&fixture).collect().unwrap();
+        assert_eq!((metrics.memory.total_kb, metrics.memory.available_kb), (1000, 600));
+        assert_eq!(metrics.load.load_average_1min, 0.5);
+        assert_eq!(metrics.cpu.cores.len(), 1);
+        assert!(metrics.pressure.is_none() && metrics.cgroup.is_none());
+        assert!(metrics.disks.is_empty() && metrics.network.is_empty() && metrics.filesystems.is_empty());
+        assert!(metrics.top_processes.iter().all(|table| table.processes.is_empty()));
+    }
+
+    #[test]
+    fn one_thermal_warning_names_the_hottest_sensor() {
+        let fixture = Fixture::new("monitor-thermal", MINIMAL_PROC);
+        for (path, content) in [
+            ("sys/class/thermal/thermal_zone0/type", "x86_pkg_temp"),
+            ("sys/class/thermal/thermal_zone0/temp", "92000"),
+            ("sys/class/hwmon/hwmon0/name", "coretemp"),
+            ("sys/class/hwmon/hwmon0/temp1_input", "95000"),
+            ("sys/class/hwmon/hwmon0/temp1_crit", "100000"),
+            // within 10°C of its own limit, so it qualifies too, but it isn't the hottest
+            ("sys/class/hwmon/hwmon0/temp2_input", "40000"),
+            ("sys/class/hwmon/hwmon0/temp2_crit", "45000"),
+            ("sys/class/hwmon/hwmon0/temp3_input", "50000"),
+        ] {
+            fixture.write(path, content);
+        }
+
+        let metrics = Collector::for_fixture(&fixture).collect().unwrap();
+        let warnings: Vec<&CriticalEvent> = metrics.critical_events.iter()
+            .filter(|e| e.event_type == "THERMAL_WARNING")
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].description.starts_with("coretemp/temp1 at 95.0°C (limit 100°C)"), "{}", warnings[0].description);
+
+        fixture.write("sys/class/thermal/thermal_zone0/temp", "30000");
+        fixture.write("sys/class/hwmon/hwmon0/temp1_input", "30000");
+        fixture.write("sys/class/hwmon/hwmon0/temp2_input", "30000");
+        let metrics = Collector::for_fixture(&fixture).collect().unwrap();
+        assert!(metrics.critical_events.iter().all(|e| e.event_type != "THERMAL_WARNING"));
+    }
+
+    #[test]
+    fn scheduler_counters_come_from_proc_stat() {
+        let fixture = Fixture::new("monitor-scheduler", MINIMAL_PROC);
+        let stat = |ctxt: u64, forks: u64| format!(
+            "cpu  100 0 50 800 0 0 0 0 0 0\ncpu0 100 0 50 800 0 0 0 0 0 0\n\
+             intr 5000 10 20 0 30\nctxt {}\nbtime 1700000000\nprocesses {}\nprocs_running 3\nprocs_blocked 1\n",
+            ctxt, forks);
+        fixture.write("proc/stat", &stat(1000, 200));
+        let mut collector = Collector::for_fixture(&fixture);
+
+        let first = collector.collect().unwrap().scheduler;
+        assert_eq!((first.context_switches, first.interrupts, first.forks), (1000, 5000, 200));
+        assert_eq!((first.procs_running, first.procs_blocked), (3, 1));
+        assert_eq!(first.context_switches_per_sec, 0.0);
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fixture.write("proc/stat", &stat(3000, 200));
+        let second = collector.collect().unwrap().scheduler;
+        assert!(second.context_switches_per_sec > 0.0);
+        assert_eq!((second.forks_per_sec, second.interrupts_per_sec), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::error::MonitorError;
+use crate::monitor::CounterSet;
+use crate::paths::{self, HostPaths};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InterfaceMetrics {
+    pub interface: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_drops: u64,
+    pub tx_drops: u64,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub rx_packets_per_sec: f64,
+    pub tx_packets_per_sec: f64,
+    pub errors_per_sec: f64,
+    pub drops_per_sec: f64,
+    pub counter_reset: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct InterfaceFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl InterfaceFilter {
+    fn accepts(&self, interface: &str) -> bool {
+        let included = self.include.is_empty()
+            || self.include.iter().any(|p| glob_match(p, interface));
+        included && !self.exclude.iter().any(|p| glob_match(p, interface))
+    }
+}
+
+// Supports '*' (any run of characters) and '?' (any single character)
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+    let (mut pi, mut ni) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, ni));
+            pi += 1;
+        } else if let Some((star_pi, star_ni)) = backtrack {
+            pi = star_pi + 1;
+            ni = star_ni + 1;
+            backtrack = Some((star_pi, star_ni + 1));
+        } else {
+            return false;
+        }
+    }
+
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+pub fn collect_net_metrics(
+    paths: &HostPaths,
+    filter: &InterfaceFilter,
+    counters: &mut CounterSet,
+) -> Result<Vec<InterfaceMetrics>, MonitorError> {
+    let path = paths.proc("net/dev");
+    let content = paths::read(&path)?;
+    parse_net_dev(&path, &content, filter, counters)
+}
+
+fn parse_net_dev(
+    path: &Path,
+    content: &str,
+    filter: &InterfaceFilter,
+    counters: &mut CounterSet,
+) -> Result<Vec<InterfaceMetrics>, MonitorError> {
+    let mut interfaces = Vec::new();
+
+    for line in content.lines().skip(2) {
+        let (name, stats) = match line.split_once(':') {
+            Some((name, stats)) => (name.trim(), stats),
+            None => continue,
+        };
+        if !filter.accepts(name) { continue; }
+
+        let parts: Vec<u64> = stats.split_whitespace()
+            .map(|s| s.parse::<u64>().unwrap_or(0))
+            .collect();
+        if parts.len() < 16 {
+            return Err(MonitorError::ParseError(format!("{}: short line for {}", path.display(), name)));
+        }
+
+        let (rx_bytes, rx_packets, rx_errors, rx_drops) = (parts[0], parts[1], parts[2], parts[3]);
+        let (tx_bytes, tx_packets, tx_errors, tx_drops) = (parts[8], parts[9], parts[10], parts[11]);
+
+        // A counter going backwards means the interface was re-created or its driver wrapped a
+        // 32-bit counter; that interval is reported as zero and the new value becomes the baseline.
+        let mut counter_reset = false;
+        let mut rate = |field: &str, value: u64| {
+            let key = format!("net.{}.{}", name, field);
+            let had_baseline = counters.has_baseline(&key);
+            match counters.delta(&key, value) {
+                Some(delta) => counters.per_sec(delta),
+                None => {
+                    counter_reset |= had_baseline;
+                    0.0
+                }
+            }
+        };
+
+        let rx_bytes_per_sec = rate("rx_bytes", rx_bytes);
+        let tx_bytes_per_sec = rate("tx_bytes", tx_bytes);
+        let rx_packets_per_sec = rate("rx_packets", rx_packets);
+        let tx_packets_per_sec = rate("tx_packets", tx_packets);
+        let errors_per_sec = rate("errors", rx_errors + tx_errors);
+        let drops_per_sec = rate("drops", rx_drops + tx_drops);
+
+        interfaces.push(InterfaceMetrics {
+            interface: name.to_string(),
+            rx_bytes,
+            tx_bytes,
+            rx_packets,
+            tx_packets,
+            rx_errors,
+            tx_errors,
+            rx_drops,
+            tx_drops,
+            rx_bytes_per_sec,
+            tx_bytes_per_sec,
+            rx_packets_per_sec,
+            tx_packets_per_sec,
+            errors_per_sec,
+            drops_per_sec,
+            counter_reset,
+        });
+    }
+
+    Ok(interfaces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NET_DEV: &str = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:    1000      10    0    0    0     0          0         0     1000      10    0    0    0     0       0          0
+  eth0: 5000000    4000    1    2    0     0          0         0  2000000    3000    3    4    0     0       0          0
+ veth1a2b:   100       1    0    0    0     0          0         0      100       1    0    0    0     0       0          0
+";
+
+    fn filter(include: &[&str], exclude: &[&str]) -> InterfaceFilter {
+        InterfaceFilter {
+            include: include.iter().map(|s| s.to_string()).collect(),
+            exclude: exclude.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn glob_match_handles_wildcards() {
+        assert!(glob_match("eth0", "eth0"));
+        assert!(!glob_match("eth0", "eth01"));
+        assert!(glob_match("eth*", "eth0"));
+        assert!(glob_match("eth*", "eth"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("veth*b", "veth1a2b"));
+        assert!(!glob_match("veth*b", "veth1a2c"));
+        assert!(glob_match("enp?s*", "enp0s3"));
+        assert!(!glob_match("en?", "en"));
+        assert!(glob_match("*a*a*", "banana"));
+    }
+
+    #[test]
+    fn parses_net_dev_and_applies_filter() {
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+
+        let interfaces = parse_net_dev(Path::new("net/dev"), NET_DEV, &filter(&[], &["lo", "veth*"]), &mut counters).unwrap();
+        assert_eq!(interfaces.len(), 1);
+        let eth0 = &interfaces[0];
+        assert_eq!(eth0.interface, "eth0");
+        assert_eq!((eth0.rx_bytes, eth0.rx_packets, eth0.rx_errors, eth0.rx_drops), (5000000, 4000, 1, 2));
+        assert_eq!((eth0.tx_bytes, eth0.tx_packets, eth0.tx_errors, eth0.tx_drops), (2000000, 3000, 3, 4));
+        assert!(!eth0.counter_reset);
+    }
+
+    #[test]
+    fn counter_going_backwards_is_a_reset() {
+        let only_eth0 = filter(&["eth0"], &[]);
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+        parse_net_dev(Path::new("net/dev"), NET_DEV, &only_eth0, &mut counters).unwrap();
+
+        counters.begin_sample();
+        let interfaces = parse_net_dev(Path::new("net/dev"), &NET_DEV.replace("5000000", "   4000"), &only_eth0, &mut counters).unwrap();
+        assert!(interfaces[0].counter_reset);
+        assert_eq!(interfaces[0].rx_bytes_per_sec, 0.0);
+    }
+
+    #[test]
+    fn short_line_is_a_parse_error() {
+        let mut counters = CounterSet::default();
+        let err = parse_net_dev(Path::new("net/dev"), "h1\nh2\n  eth0: 1 2 3\n", &filter(&[], &[]), &mut counters)
+            .unwrap_err().to_string();
+        assert!(err.contains("net/dev: short line for eth0"), "{}", err);
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use crate::meminfo::MeminfoMetrics;
+use crate::monitor::CounterSet;
+use crate::paths::HostPaths;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NumaNode {
+    pub node: u32,
+    pub mem_total_kb: u64,
+    pub mem_free_kb: u64,
+    pub mem_used_kb: u64,
+    pub used_percent: f64,
+    pub anon_pages_kb: u64,
+    pub file_pages_kb: u64,
+    pub numa_hit: u64,
+    pub numa_miss: u64,
+    pub numa_foreign: u64,
+    pub interleave_hit: u64,
+    pub local_node: u64,
+    pub other_node: u64,
+    pub numa_miss_per_sec: f64,
+    pub numa_foreign_per_sec: f64,
+}
+
+// Kernels built without NUMA have no node directory at all; the whole machine is then node 0
+pub fn collect_numa_metrics(
+    paths: &HostPaths,
+    meminfo: &MeminfoMetrics,
+    counters: &mut CounterSet,
+) -> Vec<NumaNode> {
+    let dir = paths.sys("devices/system/node");
+    let mut node_ids: Vec<u32> = fs::read_dir(&dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().to_str()?.strip_prefix("node")?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    node_ids.sort_unstable();
+
+    if node_ids.is_empty() {
+        let used = meminfo.mem_total_kb.saturating_sub(meminfo.mem_free_kb);
+        return vec![NumaNode {
+            node: 0,
+            mem_total_kb: meminfo.mem_total_kb,
+            mem_free_kb: meminfo.mem_free_kb,
+            mem_used_kb: used,
+            used_percent: percent(used, meminfo.mem_total_kb),
+            anon_pages_kb: meminfo.anon_pages_kb,
+            file_pages_kb: meminfo.cached_kb,
+            ..NumaNode::default()
+        }];
+    }
+
+    node_ids.into_iter()
+        .map(|node| {
+            let node_dir = dir.join(format!("node{}", node));
+            let mut m = NumaNode { node, ..NumaNode::default() };
+
+            // "Node 0 MemTotal:        5471992 kB"
+            if let Ok(content) = fs::read_to_string(node_dir.join("meminfo")) {
+                for line in content.lines() {
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    if parts.len() < 4 { continue; }
+                    let value = parts[3].parse::<u64>().unwrap_or(0);
+
+                    match parts[2] {
+                        "MemTotal:" => m.mem_total_kb = value,
+                        "MemFree:" => m.mem_free_kb = value,
+                        "MemUsed:" => m.mem_used_kb = value,
+                        "AnonPages:" => m.anon_pages_kb = value,
+                        "FilePages:" => m.file_pages_kb = value,
+                        _ => {}
+                    }
+                }
+            }
+            m.used_percent = percent(m.mem_used_kb, m.mem_total_kb);
+
+            if let Ok(content) = fs::read_to_string(node_dir.join("numastat")) {
+                for line in content.lines() {
+                    let (key, value) = match line.split_once(' ') {
+                        Some((key, value)) => (key, value.trim().parse::<u64>().unwrap_or(0)),
+                        None => continue,
+                    };
+
+                    match key {
+                        "numa_hit" => m.numa_hit = value,
+                        "numa_miss" => m.numa_miss = value,
+                        "numa_foreign" => m.numa_foreign = value,
+                        "interleave_hit" => m.interleave_hit = value,
+                        "local_node" => m.local_node = value,
+                        "other_node" => m.other_node = value,
+                        _ => {}
+                    }
+                }
+            }
+            m.numa_miss_per_sec = counters.rate(&format!("numa.{}.miss", node), m.numa_miss);
+            m.numa_foreign_per_sec = counters.rate(&format!("numa.{}.foreign", node), m.numa_foreign);
+
+            m
+        })
+        .collect()
+}
+
+fn percent(used: u64, total: u64) -> f64 {
+    if total > 0 { (used as f64 / total as f64) * 100.0 } else { 0.0 }
+}
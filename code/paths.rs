@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::MonitorError;
+
+const LOCAL_PROC: &str = "/proc";
+const LOCAL_SYS: &str = "/sys";
+
+// Where collectors find procfs and sysfs; a host's /proc can be mounted elsewhere in a sidecar,
+// and tests can point these at recorded fixture trees.
+#[derive(Debug, Clone)]
+pub struct HostPaths {
+    proc_root: PathBuf,
+    sys_root: PathBuf,
+}
+
+impl Default for HostPaths {
+    fn default() -> Self {
+        Self::new(LOCAL_PROC, LOCAL_SYS)
+    }
+}
+
+impl HostPaths {
+    pub fn new(proc_root: impl Into<PathBuf>, sys_root: impl Into<PathBuf>) -> Self {
+        Self { proc_root: proc_root.into(), sys_root: sys_root.into() }
+    }
+
+    pub fn is_local(&self) -> bool {
+        self.proc_root == Path::new(LOCAL_PROC) && self.sys_root == Path::new(LOCAL_SYS)
+    }
+
+    pub fn proc(&self, relative: impl AsRef<Path>) -> PathBuf {
+        self.proc_root.join(relative)
+    }
+
+    pub fn proc_root(&self) -> &Path {
+        &self.proc_root
+    }
+
+    pub fn pid(&self, pid: u32, relative: &str) -> PathBuf {
+        self.proc_root.join(pid.to_string()).join(relative)
+    }
+
+    pub fn sys(&self, relative: impl AsRef<Path>) -> PathBuf {
+        self.sys_root.join(relative)
+    }
+
+    // Mount tables list paths as the monitored host sees them; rebase those that live under /sys
+    pub fn host_sys_path(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(LOCAL_SYS) {
+            Ok(relative) => self.sys(relative),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+
+    // Per-process views of the monitored host (mounts, cgroup, root): our own when local,
+    // otherwise init's through the foreign procfs
+    pub fn host_process(&self, relative: &str) -> PathBuf {
+        if self.is_local() { self.proc("self").join(relative) } else { self.pid(1, relative) }
+    }
+
+    pub fn host_root_path(&self, path: &str) -> PathBuf {
+        if self.is_local() {
+            PathBuf::from(path)
+        } else {
+            self.host_process("root").join(path.trim_start_matches('/'))
+        }
+    }
+}
+
+pub fn read(path: &Path) -> Result<String, MonitorError> {
+    fs::read_to_string(path)
+        .map_err(|e| MonitorError::FileRead(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+pub struct Fixture {
+    pub root: PathBuf,
+    pub paths: HostPaths,
+}
+
+#[cfg(test)]
+impl Fixture {
+    pub fn new(name: &str, files: &[(&str, &str)]) -> Self {
+        let root = std::env::temp_dir().join(format!("sysmon-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("proc")).unwrap();
+        fs::create_dir_all(root.join("sys")).unwrap();
+
+        let fixture = Self { paths: HostPaths::new(root.join("proc"), root.join("sys")), root };
+        for (path, content) in files {
+            fixture.write(path, content);
+        }
+        fixture
+    }
+
+    pub fn write(&self, path: &str, content: &str) {
+        let path = self.root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
+
+#[cfg(test)]
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_paths_read_our_own_process() {
+        let paths = HostPaths::default();
+        assert!(paths.is_local());
+        assert_eq!(paths.host_process("mounts"), Path::new("/proc/self/mounts"));
+        assert_eq!(paths.host_root_path("/var/log"), Path::new("/var/log"));
+    }
+
+    #[test]
+    fn foreign_paths_go_through_init() {
+        let paths = HostPaths::new("/host/proc", "/host/sys");
+        assert!(!paths.is_local());
+        assert_eq!(paths.host_process("mounts"), Path::new("/host/proc/1/mounts"));
+        assert_eq!(paths.host_root_path("/var/log"), Path::new("/host/proc/1/root/var/log"));
+        assert_eq!(paths.host_sys_path(Path::new("/sys/fs/cgroup")), Path::new("/host/sys/fs/cgroup"));
+        assert_eq!(paths.host_sys_path(Path::new("/mnt/cgroup")), Path::new("/mnt/cgroup"));
+    }
+
+    #[test]
+    fn read_names_the_missing_file() {
+        let fixture = Fixture::new("paths-read", &[("proc/loadavg", "0.10 0.20 0.30 1/100 42\n")]);
+        assert!(read(&fixture.paths.proc("loadavg")).unwrap().starts_with("0.10"));
+
+        let err = read(&fixture.paths.proc("diskstats")).unwrap_err().to_string();
+        assert!(err.contains("diskstats"), "{}", err);
+    }
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use crate::error::MonitorError;
+use crate::monitor::CounterSet;
+use crate::paths::HostPaths;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PressureMetrics {
+    pub cpu: Option<PressureResource>,
+    pub memory: Option<PressureResource>,
+    pub io: Option<PressureResource>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PressureResource {
+    pub some: PressureLine,
+    pub full: Option<PressureLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PressureLine {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total_us: u64,
+    pub stall_percent: f64,
+}
+
+pub fn collect_pressure_metrics(
+    paths: &HostPaths,
+    counters: &mut CounterSet,
+) -> Result<Option<PressureMetrics>, MonitorError> {
+    if !paths.proc("pressure").is_dir() {
+        return Ok(None);
+    }
+
+    Ok(Some(PressureMetrics {
+        cpu: collect_pressure_resource(paths, "cpu", counters)?,
+        memory: collect_pressure_resource(paths, "memory", counters)?,
+        io: collect_pressure_resource(paths, "io", counters)?,
+    }))
+}
+
+fn collect_pressure_resource(
+    paths: &HostPaths,
+    resource: &str,
+    counters: &mut CounterSet,
+) -> Result<Option<PressureResource>, MonitorError> {
+    let path = paths.proc(format!("pressure/{}", resource)).display().to_string();
+    // the files exist but refuse reads when the kernel was booted with psi=0
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => return Ok(None),
+    };
+
+    let mut some = None;
+    let mut full = None;
+
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() { continue; }
+
+        let kind = parts[0];
+        let parsed = parse_pressure_line(&parts[1..], &path)?;
+        let stall_us = counters.rate(&format!("psi.{}.{}", resource, kind), parsed.total_us);
+        let parsed = PressureLine { stall_percent: stall_us / 10_000.0, ..parsed };
+
+        match kind {
+            "some" => some = Some(parsed),
+            "full" => full = Some(parsed),
+            _ => {}
+        }
+    }
+
+    let some = some
+        .ok_or_else(|| MonitorError::ParseError(format!("{}: missing 'some' line", path)))?;
+
+    Ok(Some(PressureResource { some, full }))
+}
+
+fn parse_pressure_line(fields: &[&str], path: &str) -> Result<PressureLine, MonitorError> {
+    let mut line = PressureLine { avg10: 0.0, avg60: 0.0, avg300: 0.0, total_us: 0, stall_percent: 0.0 };
+
+    for field in fields {
+        let (key, value) = field.split_once('=')
+            .ok_or_else(|| MonitorError::ParseError(format!("{}: bad field '{}'", path, field)))?;
+
+        match key {
+            "avg10" => line.avg10 = parse_value(value, path, key)?,
+            "avg60" => line.avg60 = parse_value(value, path, key)?,
+            "avg300" => line.avg300 = parse_value(value, path, key)?,
+            "total" => line.total_us = parse_value(value, path, key)?,
+            _ => {}
+        }
+    }
+
+    Ok(line)
+}
+
+fn parse_value<T>(value: &str, path: &str, key: &str) -> Result<T, MonitorError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value.parse()
+        .map_err(|e| MonitorError::ParseError(format!("{}: {}: {}", path, key, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pressure_fields() {
+        let fields: Vec<&str> = "avg10=1.50 avg60=0.75 avg300=0.10 total=123456".split_whitespace().collect();
+        let line = parse_pressure_line(&fields, "/proc/pressure/io").unwrap();
+        assert_eq!((line.avg10, line.avg60, line.avg300, line.total_us), (1.5, 0.75, 0.1, 123456));
+    }
+
+    #[test]
+    fn malformed_pressure_field_names_the_file() {
+        let err = parse_pressure_line(&["avg10=abc"], "/proc/pressure/io").unwrap_err().to_string();
+        assert!(err.contains("/proc/pressure/io") && err.contains("avg10"), "{}", err);
+        assert!(parse_pressure_line(&["avg10"], "/proc/pressure/io").is_err());
+    }
+}
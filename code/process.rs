@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs;
+use crate::config::ProcessSortKey;
+use crate::error::MonitorError;
+use crate::monitor::CounterSet;
+use crate::paths::{self, HostPaths};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub comm: String,
+    pub state: String,
+    pub rss_kb: u64,
+    pub cpu_percent: f64,
+    pub threads: u64,
+    pub uid: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessTable {
+    pub sort_key: ProcessSortKey,
+    pub processes: Vec<ProcessInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessTableOptions {
+    pub top_n: usize,
+    pub sort_keys: Vec<ProcessSortKey>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcStat {
+    pub pid: u32,
+    pub comm: String,
+    pub state: char,
+    pub ppid: u32,
+    pub utime_ticks: u64,
+    pub stime_ticks: u64,
+    pub threads: u64,
+    pub start_ticks: u64,
+    pub rss_pages: u64,
+}
+
+impl ProcStat {
+    pub fn cpu_ticks(&self) -> u64 {
+        self.utime_ticks + self.stime_ticks
+    }
+}
+
+pub fn clock_ticks_per_sec() -> u64 {
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 { ticks as u64 } else { 100 }
+}
+
+pub fn page_size_kb() -> u64 {
+    let ps = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if ps > 0 { ps as u64 / 1024 } else { 4 }
+}
+
+pub fn list_pids(paths: &HostPaths) -> Result<Vec<u32>, MonitorError> {
+    let root = paths.proc_root();
+    let entries = fs::read_dir(root)
+        .map_err(|e| MonitorError::FileRead(format!("{}: {}", root.display(), e)))?;
+
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()))
+        .collect())
+}
+
+pub fn read_proc_stat(paths: &HostPaths, pid: u32) -> Result<ProcStat, MonitorError> {
+    let path = paths.pid(pid, "stat");
+    let content = paths::read(&path)?;
+
+    // comm may itself contain spaces and parentheses, so split around the last ')'
+    let open = content.find('(');
+    let close = content.rfind(')');
+    let (open, close) = match (open, close) {
+        (Some(open), Some(close)) if open < close => (open, close),
+        _ => return Err(MonitorError::ParseError(format!("{}: missing comm", path.display()))),
+    };
+
+    let comm = content[open + 1..close].to_string();
+    let rest: Vec<&str> = content[close + 1..].split_whitespace().collect();
+    if rest.len() < 22 {
+        return Err(MonitorError::ParseError(format!("{}: too few fields", path.display())));
+    }
+    let field = |i: usize| rest[i].parse::<u64>().unwrap_or(0);
+
+    Ok(ProcStat {
+        pid,
+        comm,
+        state: rest[0].chars().next().unwrap_or('?'),
+        ppid: field(1) as u32,
+        utime_ticks: field(11),
+        stime_ticks: field(12),
+        threads: field(17),
+        start_ticks: field(19),
+        rss_pages: field(21),
+    })
+}
+
+pub fn read_proc_status(paths: &HostPaths, pid: u32) -> Result<Vec<(String, String)>, MonitorError> {
+    let content = paths::read(&paths.pid(pid, "status"))?;
+
+    Ok(content.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+        .collect())
+}
+
+// first number of a status value: "1804 kB" -> 1804, "1000\t1000\t1000\t1000" -> 1000
+pub fn status_u64(status: &[(String, String)], key: &str) -> Option<u64> {
+    status.iter()
+        .find(|(k, _)| k == key)
+        .and_then(|(_, v)| v.split_whitespace().next())
+        .and_then(|v| v.parse().ok())
+}
+
+// CPU time is keyed by pid and start time so a recycled pid never inherits another process' ticks;
+// scope separates callers that sample the same process within one collection
+pub fn cpu_percent(scope: &str, stat: &ProcStat, ticks_per_sec: u64, counters: &mut CounterSet) -> f64 {
+    let key = format!("{}.{}.{}.cpu", scope, stat.pid, stat.start_ticks);
+    let delta = counters.delta(&key, stat.cpu_ticks()).unwrap_or(0);
+    counters.per_sec(delta) / ticks_per_sec as f64 * 100.0
+}
+
+pub fn collect_process_tables(
+    paths: &HostPaths,
+    options: &ProcessTableOptions,
+    counters: &mut CounterSet,
+) -> Result<Vec<ProcessTable>, MonitorError> {
+    if options.top_n == 0 || options.sort_keys.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ticks_per_sec = clock_ticks_per_sec();
+    let page_kb = page_size_kb();
+
+    // processes exit between listing /proc and reading their files; those are simply skipped
+    let mut candidates: Vec<(ProcStat, f64)> = list_pids(paths)?
+        .into_iter()
+        .filter_map(|pid| read_proc_stat(paths, pid).ok())
+        .map(|stat| {
+            let cpu = cpu_percent("top", &stat, ticks_per_sec, counters);
+            (stat, cpu)
+        })
+        .collect();
+
+    let mut tables = Vec::new();
+    for &sort_key in &options.sort_keys {
+        match sort_key {
+            ProcessSortKey::Rss => candidates.sort_by_key(|c| Reverse(c.0.rss_pages)),
+            ProcessSortKey::Cpu => candidates.sort_by(|a, b| b.1.total_cmp(&a.1)),
+            ProcessSortKey::Threads => candidates.sort_by_key(|c| Reverse(c.0.threads)),
+        }
+
+        let processes = candidates.iter()
+            .take(options.top_n)
+            .map(|(stat, cpu)| {
+                let status = read_proc_status(paths, stat.pid).unwrap_or_default();
+                ProcessInfo {
+                    pid: stat.pid,
+                    comm: stat.comm.clone(),
+                    state: stat.state.to_string(),
+                    rss_kb: status_u64(&status, "VmRSS").unwrap_or(stat.rss_pages * page_kb),
+                    cpu_percent: *cpu,
+                    threads: stat.threads,
+                    uid: status_u64(&status, "Uid").unwrap_or(0) as u32,
+                }
+            })
+            .collect();
+
+        tables.push(ProcessTable { sort_key, processes });
+    }
+
+    Ok(tables)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SelfMetrics {
+    pub pid: u32,
+    pub rss_kb: u64,
+    pub swap_kb: u64,
+    pub threads: u64,
+    pub fd_count: u64,
+    pub cpu_time_secs: f64,
+    pub cpu_percent: f64,
+    pub monitor_thread_cpu_secs: f64,
+}
+
+pub fn count_fds(paths: &HostPaths, pid: u32) -> Result<u64, MonitorError> {
+    let path = paths.pid(pid, "fd");
+    let entries = fs::read_dir(&path)
+        .map_err(|e| MonitorError::FileRead(format!("{}: {}", path.display(), e)))?;
+    Ok(entries.count() as u64)
+}
+
+pub fn collect_self_metrics(counters: &mut CounterSet) -> Result<SelfMetrics, MonitorError> {
+    // our own pid only means something in the procfs we run under, whatever --proc-root says
+    let paths = HostPaths::default();
+    let pid = std::process::id();
+    let ticks_per_sec = clock_ticks_per_sec();
+
+    let stat = read_proc_stat(&paths, pid)?;
+    let status = read_proc_status(&paths, pid)?;
+    // the main thread only samples and reports, so its CPU time is the monitor's own overhead
+    let main_thread = fs::read_to_string(paths.pid(pid, &format!("task/{}/stat", pid)))
+        .ok()
+        .and_then(|content| {
+            let rest: Vec<&str> = content[content.rfind(')')? + 1..].split_whitespace().collect();
+            let utime = rest.get(11)?.parse::<u64>().ok()?;
+            let stime = rest.get(12)?.parse::<u64>().ok()?;
+            Some(utime + stime)
+        })
+        .unwrap_or(0);
+
+    Ok(SelfMetrics {
+        pid,
+        rss_kb: status_u64(&status, "VmRSS").unwrap_or(stat.rss_pages * page_size_kb()),
+        swap_kb: status_u64(&status, "VmSwap").unwrap_or(0),
+        threads: stat.threads,
+        // minus the descriptor read_dir itself holds open while listing
+        fd_count: count_fds(&paths, pid)?.saturating_sub(1),
+        cpu_time_secs: stat.cpu_ticks() as f64 / ticks_per_sec as f64,
+        cpu_percent: counters.rate("self.cpu", stat.cpu_ticks()) / ticks_per_sec as f64 * 100.0,
+        monitor_thread_cpu_secs: main_thread as f64 / ticks_per_sec as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::Fixture;
+
+    fn stat_line(pid: u32, comm: &str, threads: u64, rss_pages: u64) -> String {
+        format!("{} ({}) S 1 0 0 0 0 0 0 0 0 0 10 5 0 0 20 0 {} 0 100 0 {} 0 0\n", pid, comm, threads, rss_pages)
+    }
+
+    fn process_fixture(name: &str) -> Fixture {
+        let fixture = Fixture::new(name, &[]);
+        for (pid, comm, threads, rss_pages) in [(10, "small", 1, 100), (20, "big", 2, 900), (30, "many", 40, 500)] {
+            fixture.write(&format!("proc/{}/stat", pid), &stat_line(pid, comm, threads, rss_pages));
+        }
+        fixture.write("proc/20/status", "Name:\tbig\nVmRSS:\t  4000 kB\nUid:\t1000\t1000\t1000\t1000\n");
+        // not a process
+        fixture.write("proc/meminfo", "MemTotal: 1 kB\n");
+        fixture
+    }
+
+    #[test]
+    fn status_values_take_the_first_number() {
+        let status = vec![
+            ("VmRSS".to_string(), "1804 kB".to_string()),
+            ("Uid".to_string(), "1000\t1000\t1000\t1000".to_string()),
+            ("State".to_string(), "S (sleeping)".to_string()),
+        ];
+        assert_eq!(status_u64(&status, "VmRSS"), Some(1804));
+        assert_eq!(status_u64(&status, "Uid"), Some(1000));
+        assert_eq!(status_u64(&status, "State"), None);
+        assert_eq!(status_u64(&status, "VmSwap"), None);
+    }
+
+    #[test]
+    fn comm_may_contain_parentheses() {
+        let fixture = Fixture::new("process-comm", &[("proc/42/stat", &stat_line(42, "a) (b", 3, 7))]);
+        let stat = read_proc_stat(&fixture.paths, 42).unwrap();
+        assert_eq!(stat.comm, "a) (b");
+        assert_eq!((stat.state, stat.ppid), ('S', 1));
+        assert_eq!((stat.cpu_ticks(), stat.threads, stat.start_ticks, stat.rss_pages), (15, 3, 100, 7));
+
+        fixture.write("proc/42/stat", "42 (short) S 1 0 0\n");
+        let err = read_proc_stat(&fixture.paths, 42).unwrap_err().to_string();
+        assert!(err.contains("too few fields"), "{}", err);
+    }
+
+    #[test]
+    fn tables_are_sorted_and_cut_to_top_n() {
+        let fixture = process_fixture("process-tables");
+        let options = ProcessTableOptions { top_n: 2, sort_keys: vec![ProcessSortKey::Rss, ProcessSortKey::Threads] };
+        let tables = collect_process_tables(&fixture.paths, &options, &mut CounterSet::default()).unwrap();
+        assert_eq!(tables.len(), 2);
+
+        let pids = |table: &ProcessTable| -> Vec<u32> { table.processes.iter().map(|p| p.pid).collect() };
+        assert_eq!(pids(&tables[0]), [20, 30]);
+        assert_eq!(pids(&tables[1]), [30, 20]);
+
+        // VmRSS from status wins over the page count in stat
+        let big = &tables[0].processes[0];
+        assert_eq!((big.comm.as_str(), big.rss_kb, big.uid), ("big", 4000, 1000));
+        assert_eq!(tables[0].processes[1].rss_kb, 500 * page_size_kb());
+
+        let none = ProcessTableOptions { top_n: 0, sort_keys: vec![ProcessSortKey::Rss] };
+        assert!(collect_process_tables(&fixture.paths, &none, &mut CounterSet::default()).unwrap().is_empty());
+    }
+}
@@ -1,7 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::fs::{self};
 use std::path::PathBuf;
+use crate::disk::DiskMetrics;
+use crate::fd::ProcessFdUsage;
+use crate::leak_detect::LeakAnalysis;
+use crate::memory_leak::{self, CpuWorkerStats, LeakNodeBytes, StressCounters};
 use crate::monitor::SystemMetrics;
+use crate::net::InterfaceMetrics;
+use crate::numa::NumaNode;
+use crate::sockets::ProtocolMetrics;
+use crate::thermal::CoreFrequency;
+use crate::watch::ProcessTreeMetrics;
+use crate::pressure::PressureResource;
 use crate::error::MonitorError;
 use crate::config::Config;
 
@@ -10,6 +20,8 @@ pub struct Snapshot {
     pub iteration: usize,
     pub timestamp: chrono::DateTime<chrono::Local>,
     pub metrics: SystemMetrics,
+    #[serde(default)]
+    pub stress: StressCounters,
 }
 
 impl Snapshot {
@@ -18,6 +30,7 @@ impl Snapshot {
             iteration,
             timestamp: chrono::Local::now(),
             metrics,
+            stress: memory_leak::stress_counters(),
         }
     }
 
@@ -27,15 +40,203 @@ impl Snapshot {
         let s = &self.metrics.swap;
         let c = &self.metrics.cpu;
 
-        println!("  MEM: {:.1}% ({}/{} MB) | LOAD: {:.2} ({:.0}%) | CPU: {:.1}% | SWAP: {:.1}%",
+        let scope = |limited: bool| if limited { " (cgroup)" } else { "" };
+        println!("  MEM: {:.1}%{} ({}/{} MB) | LOAD: {:.2} ({:.0}%) | CPU: {:.1}%{} | SWAP: {:.1}%",
                  m.used_percent,
+                 scope(m.cgroup_limited),
                  m.used_kb / 1024,
                  m.total_kb / 1024,
                  l.load_average_1min,
                  l.load_percent_1min,
                  c.cpu_usage_percent,
+                 scope(c.cgroup_limited),
                  s.used_percent);
 
+        if let Some(cg) = &self.metrics.cgroup {
+            let mb = |b: u64| b as f64 / (1024.0 * 1024.0);
+            let limit = cg.memory_max_bytes.map(|b| format!("{:.0} MB", mb(b))).unwrap_or_else(|| "max".into());
+            let cpu_limit = cg.cpu.as_ref().and_then(|c| c.limit_cores)
+                .map(|cores| format!("{:.2} cores", cores)).unwrap_or_else(|| "max".into());
+            println!("  CGROUP {}: mem {:.1} MB / {} | cpu {:.2} cores / {} | throttled {:.1}%",
+                     cg.path,
+                     mb(cg.memory_working_set_bytes.unwrap_or(0)),
+                     limit,
+                     cg.cpu.as_ref().map(|c| c.usage_cores).unwrap_or(0.0),
+                     cpu_limit,
+                     cg.cpu.as_ref().map(|c| c.throttled_percent).unwrap_or(0.0));
+        }
+
+        let mi = &self.metrics.meminfo;
+        println!("  MEMINFO: anon {} MB | shmem {} MB | dirty {} MB | writeback {} MB | slab {} MB (unreclaimable {} MB) | pagetables {} MB | commit {:.1}% ({}/{} MB)",
+                 mi.anon_pages_kb / 1024,
+                 mi.shmem_kb / 1024,
+                 mi.dirty_kb / 1024,
+                 mi.writeback_kb / 1024,
+                 mi.slab_kb / 1024,
+                 mi.sunreclaim_kb / 1024,
+                 mi.page_tables_kb / 1024,
+                 mi.committed_percent,
+                 mi.committed_as_kb / 1024,
+                 mi.commit_limit_kb / 1024);
+        if mi.huge_pages_total > 0 {
+            println!("  HUGEPAGES: {} total | {} free | {} reserved | {} surplus ({} KB pages)",
+                     mi.huge_pages_total, mi.huge_pages_free, mi.huge_pages_rsvd,
+                     mi.huge_pages_surp, mi.hugepagesize_kb);
+        }
+
+        let st = &c.states;
+        println!("  CPU STATES: usr {:.1}% | nice {:.1}% | sys {:.1}% | iowait {:.1}% | irq {:.1}% | softirq {:.1}% | steal {:.1}%",
+                 st.user, st.nice, st.system, st.iowait, st.irq, st.softirq, st.steal);
+
+        for n in &self.metrics.numa_nodes {
+            println!("  NUMA node{}: {:.1}% used ({}/{} MB) | anon {} MB | file {} MB | miss {:.0}/s foreign {:.0}/s",
+                     n.node, n.used_percent,
+                     n.mem_used_kb / 1024, n.mem_total_kb / 1024,
+                     n.anon_pages_kb / 1024, n.file_pages_kb / 1024,
+                     n.numa_miss_per_sec, n.numa_foreign_per_sec);
+        }
+        if !self.stress.cpu_workers.is_empty() {
+            let workers: Vec<String> = self.stress.cpu_workers.iter()
+                .map(|w| format!("#{} {:.1}%/{}% {:.3e} {}/cpu-s",
+                                 w.worker, w.achieved_percent, w.target_percent,
+                                 w.ops_per_cpu_sec, w.workload.unit()))
+                .collect();
+            println!("  CPU WORKERS (achieved/target): {}", workers.join(" | "));
+        }
+        if self.stress.stressors_released {
+            println!("  GUARD: stressors stopped, leak released");
+        } else if self.stress.stressors_paused {
+            println!("  GUARD: stressors paused");
+        }
+        if !self.stress.leak_node_bytes.is_empty() {
+            let nodes: Vec<String> = self.stress.leak_node_bytes.iter()
+                .map(|n| format!("node{} {:.1} MB", n.node, n.bytes as f64 / (1024.0 * 1024.0)))
+                .collect();
+            println!("  LEAK NODES: {}", nodes.join(" | "));
+        }
+
+        let sc = &self.metrics.scheduler;
+        println!("  SCHED: ctxt {:.0}/s | intr {:.0}/s | forks {:.1}/s | running {} | blocked {}",
+                 sc.context_switches_per_sec, sc.interrupts_per_sec, sc.forks_per_sec,
+                 sc.procs_running, sc.procs_blocked);
+
+        let v = &self.metrics.vmstat;
+        println!("  VMSTAT: faults {:.0}/s (major {:.0}/s) | swap in {:.0}/s out {:.0}/s | page in {:.0}/s out {:.0}/s | scan {:.0}/s",
+                 v.pgfault_per_sec, v.pgmajfault_per_sec,
+                 v.pswpin_per_sec, v.pswpout_per_sec,
+                 v.pgpgin_per_sec, v.pgpgout_per_sec,
+                 v.pgscan_per_sec);
+
+        for d in &self.metrics.disks {
+            println!("  DISK {}: r {:.1} MB/s w {:.1} MB/s | {:.0} r/s {:.0} w/s | lat r {:.1} w {:.1} ms | q {:.2} | util {:.1}%",
+                     d.device,
+                     d.read_bytes_per_sec / (1024.0 * 1024.0),
+                     d.write_bytes_per_sec / (1024.0 * 1024.0),
+                     d.read_iops, d.write_iops,
+                     d.read_latency_ms, d.write_latency_ms,
+                     d.avg_queue_depth,
+                     d.util_percent);
+        }
+
+        for f in &self.metrics.filesystems {
+            println!("  FS {}: {:.1}% ({:.1}/{:.1} GB) | inodes {:.1}%{}",
+                     f.mount_point,
+                     f.used_percent,
+                     f.used_bytes as f64 / (1024.0 * 1024.0 * 1024.0),
+                     f.total_bytes as f64 / (1024.0 * 1024.0 * 1024.0),
+                     f.inodes_used_percent,
+                     if f.read_only { " [ro]" } else { "" });
+        }
+
+        for n in &self.metrics.network {
+            println!("  NET {}: rx {:.1} KB/s ({:.0} pkt/s) tx {:.1} KB/s ({:.0} pkt/s) | err {:.1}/s drop {:.1}/s{}",
+                     n.interface,
+                     n.rx_bytes_per_sec / 1024.0, n.rx_packets_per_sec,
+                     n.tx_bytes_per_sec / 1024.0, n.tx_packets_per_sec,
+                     n.errors_per_sec, n.drops_per_sec,
+                     if n.counter_reset { " (counter reset)" } else { "" });
+        }
+
+        if let Some(p) = &self.metrics.protocols {
+            let (tcp, udp) = (&p.tcp, &p.udp);
+            println!("  TCP: estab {} | tw {} | orphan {} | opens {:.1}/s active, {:.1}/s passive | resets {:.1}/s | retrans {:.1}/s ({:.2}%) | listen drops {:.1}/s",
+                     tcp.established, tcp.time_wait, tcp.orphans,
+                     tcp.active_opens_per_sec, tcp.passive_opens_per_sec,
+                     tcp.resets_per_sec,
+                     tcp.retrans_segs_per_sec, tcp.retrans_percent,
+                     tcp.listen_drops_per_sec);
+            println!("  UDP: in {:.0}/s out {:.0}/s | errors {:.1}/s (rcvbuf {:.1}/s) | sockets {}",
+                     udp.in_datagrams_per_sec, udp.out_datagrams_per_sec,
+                     udp.in_errors_per_sec, udp.rcvbuf_errors_per_sec,
+                     udp.in_use);
+        }
+
+        if let Some(p) = &self.metrics.pressure {
+            let avg10 = |r: &Option<PressureResource>| r.as_ref().map(|r| r.some.avg10).unwrap_or(0.0);
+            println!("  PSI some avg10: cpu {:.1}% | mem {:.1}% | io {:.1}%",
+                     avg10(&p.cpu), avg10(&p.memory), avg10(&p.io));
+        }
+
+        if !c.cores.is_empty() {
+            let cores: Vec<String> = c.cores.iter()
+                .map(|core| format!("{}:{:.0}%", core.core, core.usage_percent))
+                .collect();
+            println!("  CORES: {}", cores.join(" "));
+        }
+
+        if let Some(w) = &self.metrics.watched {
+            println!("  WATCH pid {} ({} procs): rss {:.1} MB | swap {:.1} MB | cpu {:.1}% | thr {} | fds {} | io r {:.1} KB/s w {:.1} KB/s",
+                     w.root_pid, w.process_count,
+                     w.rss_kb as f64 / 1024.0,
+                     w.swap_kb as f64 / 1024.0,
+                     w.cpu_percent, w.threads, w.fd_count,
+                     w.read_bytes_per_sec / 1024.0,
+                     w.write_bytes_per_sec / 1024.0);
+        }
+
+        if let Some(l) = &self.metrics.leak_analysis {
+            println!("  LEAK pid {}: anon {:+.1} MB | file {:+.1} MB | shmem {:+.1} MB | swap {:+.1} MB | trend {:.1} MB/h (R² {:.2}){}",
+                     l.pid,
+                     l.anon_growth_kb as f64 / 1024.0,
+                     l.file_growth_kb as f64 / 1024.0,
+                     l.shmem_growth_kb as f64 / 1024.0,
+                     l.swap_growth_kb as f64 / 1024.0,
+                     l.leak_mb_per_hour, l.r_squared,
+                     if l.suspected { " SUSPECTED" } else { "" });
+        }
+
+        let t = &self.metrics.thermal;
+        if let Some((sensor, temp_c)) = t.hottest() {
+            println!("  THERMAL: hottest {} {:.1}°C ({} zones, {} sensors)",
+                     sensor, temp_c, t.zones.len(), t.sensors.len());
+        }
+        if let Some(slowest) = t.lowest_frequency() {
+            let avg = t.frequencies.iter().map(|f| f.current_mhz).sum::<f64>() / t.frequencies.len() as f64;
+            println!("  FREQ: avg {:.0} MHz | slowest cpu{} {:.0} MHz ({:.0}% of {:.0} MHz max)",
+                     avg, slowest.core, slowest.current_mhz, slowest.percent_of_max, slowest.max_mhz);
+        }
+
+        let fds = &self.metrics.fds;
+        // max is 0 when file-nr couldn't be read for this sample
+        if fds.max > 0 {
+            println!("  FDS: {} / {} ({:.2}%) system-wide", fds.allocated, fds.max, fds.used_percent);
+        }
+        for p in &fds.processes {
+            println!("    {:>7} {:<16} fds {:>6} / {:<9} {}{}",
+                     p.pid, p.comm, p.fd_count,
+                     p.soft_limit.map(|l| l.to_string()).unwrap_or_else(|| "unlimited".into()),
+                     p.used_percent.map(|pct| format!("({:.1}%)", pct)).unwrap_or_default(),
+                     if p.growing { format!(" GROWING {:+}", p.growth) } else { String::new() });
+        }
+
+        for table in &self.metrics.top_processes {
+            println!("  TOP by {:?}:", table.sort_key);
+            for p in &table.processes {
+                println!("    {:>7} {:<16} {} rss {:>8} KB | cpu {:>5.1}% | thr {:>4} | uid {}",
+                         p.pid, p.comm, p.state, p.rss_kb, p.cpu_percent, p.threads, p.uid);
+            }
+        }
+
         if !self.metrics.critical_events.is_empty() {
             println!("{} critical events detected", self.metrics.critical_events.len());
         }
@@ -74,10 +275,150 @@ pub struct Statistics {
     pub cpu_stats: ResourceStats,
     pub load_stats: ResourceStats,
     pub swap_stats: ResourceStats,
+    pub iowait_stats: ResourceStats,
+    pub steal_stats: ResourceStats,
+    pub major_fault_stats: ResourceStats,
+    pub swap_activity_stats: ResourceStats,
+    pub commit_stats: ResourceStats,
+    pub context_switch_stats: ResourceStats,
+    pub fork_stats: ResourceStats,
+    pub blocked_stats: ResourceStats,
+    pub total_oom_kills: u64,
+    pub per_core_stats: Vec<CoreStats>,
+    pub per_disk_stats: Vec<DiskStats>,
+    pub per_interface_stats: Vec<InterfaceStats>,
+    pub per_numa_node_stats: Vec<NumaNodeStats>,
+    pub leak_node_bytes: Vec<LeakNodeBytes>,
+    pub protocol_stats: ProtocolStats,
+    pub fd_stats: FdStats,
+    pub self_observation: SelfObservation,
+    pub watch_stats: Option<WatchStats>,
+    pub leak_analysis: Option<LeakAnalysis>,
+    pub thermal_stats: Option<ThermalStats>,
     pub total_critical_events: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+pub struct CoreStats {
+    pub core: usize,
+    pub usage: ResourceStats,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiskStats {
+    pub device: String,
+    pub read_bytes_per_sec: ResourceStats,
+    pub write_bytes_per_sec: ResourceStats,
+    pub iops: ResourceStats,
+    pub latency_ms: ResourceStats,
+    pub util_percent: ResourceStats,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InterfaceStats {
+    pub interface: String,
+    pub rx_bytes_per_sec: ResourceStats,
+    pub tx_bytes_per_sec: ResourceStats,
+    pub errors_per_sec: ResourceStats,
+    pub drops_per_sec: ResourceStats,
+    pub counter_resets: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NumaNodeStats {
+    pub node: u32,
+    pub mem_total_kb: u64,
+    pub used_percent: ResourceStats,
+    pub anon_pages_kb: ResourceStats,
+    pub total_numa_miss: u64,
+    pub total_numa_foreign: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct FdStats {
+    pub system_used_percent: ResourceStats,
+    pub system_allocated: ResourceStats,
+    pub processes: Vec<ProcessFdStats>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessFdStats {
+    pub pid: u32,
+    pub comm: String,
+    pub peak_fd_count: u64,
+    pub soft_limit: Option<u64>,
+    pub peak_used_percent: Option<f64>,
+    pub growth: i64,
+    pub samples: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ProtocolStats {
+    pub tcp_established: ResourceStats,
+    pub tcp_time_wait: ResourceStats,
+    pub tcp_orphans: ResourceStats,
+    pub tcp_active_opens_per_sec: ResourceStats,
+    pub tcp_passive_opens_per_sec: ResourceStats,
+    pub tcp_resets_per_sec: ResourceStats,
+    pub tcp_retrans_percent: ResourceStats,
+    pub total_tcp_retrans_segs: u64,
+    pub total_tcp_resets: u64,
+    pub total_tcp_listen_drops: u64,
+    pub udp_in_datagrams_per_sec: ResourceStats,
+    pub total_udp_rcvbuf_errors: u64,
+    pub total_udp_in_errors: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct SelfObservation {
+    pub leak_claimed_bytes: u64,
+    pub leak_peak_bytes: u64,
+    pub leak_freed_bytes: u64,
+    pub rss_growth_kb: i64,
+    pub swap_growth_kb: i64,
+    pub leak_in_ram_percent: f64,
+    pub leak_in_swap_percent: f64,
+    pub peak_rss_kb: u64,
+    pub peak_swap_kb: u64,
+    pub peak_threads: u64,
+    pub peak_fd_count: u64,
+    pub cpu_total_ops: u64,
+    pub cpu_ops_unit: String,
+    pub process_cpu_secs: f64,
+    pub ops_per_cpu_sec: f64,
+    pub monitor_cpu_secs: f64,
+    pub monitor_cpu_percent: f64,
+    pub cpu_workers: Vec<CpuWorkerStats>,
+    pub disk_write_bytes: u64,
+    pub disk_read_bytes: u64,
+    pub disk_fsyncs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WatchStats {
+    pub root_pid: u32,
+    pub rss_kb: ResourceStats,
+    pub cpu_percent: ResourceStats,
+    pub threads: ResourceStats,
+    pub fd_count: ResourceStats,
+    pub max_process_count: usize,
+    pub total_read_bytes: u64,
+    pub total_write_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThermalStats {
+    pub peak_temp_c: Option<f64>,
+    pub peak_sensor: Option<String>,
+    pub hottest_temp_c: ResourceStats,
+    pub max_freq_mhz: f64,
+    pub highest_freq_mhz: f64,
+    pub lowest_freq_mhz: f64,
+    pub avg_freq_mhz: f64,
+    pub max_freq_drop_percent: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct ResourceStats {
     pub min: f64,
     pub max: f64,
@@ -118,6 +459,26 @@ impl FinalReport {
                 cpu_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
                 load_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
                 swap_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
+                iowait_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
+                steal_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
+                major_fault_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
+                swap_activity_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
+                commit_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
+                context_switch_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
+                fork_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
+                blocked_stats: ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 },
+                total_oom_kills: 0,
+                per_core_stats: Vec::new(),
+                per_disk_stats: Vec::new(),
+                per_interface_stats: Vec::new(),
+                per_numa_node_stats: Vec::new(),
+                leak_node_bytes: Vec::new(),
+                protocol_stats: ProtocolStats::default(),
+                fd_stats: FdStats::default(),
+                self_observation: SelfObservation::default(),
+                watch_stats: None,
+                leak_analysis: None,
+                thermal_stats: None,
                 total_critical_events: 0,
             };
         }
@@ -139,6 +500,47 @@ impl FinalReport {
         let swap_values: Vec<f64> = snapshots.iter().map(|s| s.metrics.swap.used_percent).collect();
         let swap_stats = Self::calc_stats(&swap_values);
 
+        let iowait_values: Vec<f64> = snapshots.iter().map(|s| s.metrics.cpu.states.iowait).collect();
+        let iowait_stats = Self::calc_stats(&iowait_values);
+
+        let steal_values: Vec<f64> = snapshots.iter().map(|s| s.metrics.cpu.states.steal).collect();
+        let steal_stats = Self::calc_stats(&steal_values);
+
+        let major_fault_values: Vec<f64> = snapshots.iter().map(|s| s.metrics.vmstat.pgmajfault_per_sec).collect();
+        let major_fault_stats = Self::calc_stats(&major_fault_values);
+
+        let swap_activity_values: Vec<f64> = snapshots.iter()
+            .map(|s| s.metrics.vmstat.pswpin_per_sec + s.metrics.vmstat.pswpout_per_sec)
+            .collect();
+        let swap_activity_stats = Self::calc_stats(&swap_activity_values);
+
+        let commit_values: Vec<f64> = snapshots.iter().map(|s| s.metrics.meminfo.committed_percent).collect();
+        let commit_stats = Self::calc_stats(&commit_values);
+
+        let context_switch_values: Vec<f64> = snapshots.iter().map(|s| s.metrics.scheduler.context_switches_per_sec).collect();
+        let context_switch_stats = Self::calc_stats(&context_switch_values);
+
+        let fork_values: Vec<f64> = snapshots.iter().map(|s| s.metrics.scheduler.forks_per_sec).collect();
+        let fork_stats = Self::calc_stats(&fork_values);
+
+        let blocked_values: Vec<f64> = snapshots.iter().map(|s| s.metrics.scheduler.procs_blocked as f64).collect();
+        let blocked_stats = Self::calc_stats(&blocked_values);
+
+        let total_oom_kills: u64 = snapshots.iter().map(|s| s.metrics.vmstat.oom_kill_delta).sum();
+
+        let per_core_stats = Self::calc_core_stats(snapshots);
+        let per_disk_stats = Self::calc_disk_stats(snapshots);
+        let per_interface_stats = Self::calc_interface_stats(snapshots);
+        let protocol_stats = Self::calc_protocol_stats(snapshots);
+        let fd_stats = Self::calc_fd_stats(snapshots);
+        let per_numa_node_stats = Self::calc_numa_node_stats(snapshots);
+        let leak_node_bytes = snapshots.last().map(|s| s.stress.leak_node_bytes.clone()).unwrap_or_default();
+        let self_observation = Self::calc_self_observation(snapshots, duration);
+        let watch_stats = Self::calc_watch_stats(snapshots);
+        // each sample's fit already covers the whole run up to that point
+        let leak_analysis = snapshots.iter().rev().find_map(|s| s.metrics.leak_analysis.clone());
+        let thermal_stats = Self::calc_thermal_stats(snapshots);
+
         let total_critical_events: usize = snapshots.iter()
             .map(|s| s.metrics.critical_events.len())
             .sum();
@@ -150,10 +552,311 @@ impl FinalReport {
             cpu_stats,
             load_stats,
             swap_stats,
+            iowait_stats,
+            steal_stats,
+            major_fault_stats,
+            swap_activity_stats,
+            commit_stats,
+            context_switch_stats,
+            fork_stats,
+            blocked_stats,
+            total_oom_kills,
+            per_core_stats,
+            per_disk_stats,
+            per_interface_stats,
+            per_numa_node_stats,
+            leak_node_bytes,
+            protocol_stats,
+            fd_stats,
+            self_observation,
+            watch_stats,
+            leak_analysis,
+            thermal_stats,
             total_critical_events,
         }
     }
 
+    // Peak temperature and how far a busy core's clock fell below its hardware maximum
+    fn calc_thermal_stats(snapshots: &[Snapshot]) -> Option<ThermalStats> {
+        let hottest: Vec<(String, f64)> = snapshots.iter()
+            .filter_map(|s| s.metrics.thermal.hottest())
+            .collect();
+        let frequencies: Vec<&CoreFrequency> = snapshots.iter()
+            .flat_map(|s| s.metrics.thermal.frequencies.iter())
+            .collect();
+        if hottest.is_empty() && frequencies.is_empty() {
+            return None;
+        }
+
+        let peak = hottest.iter().max_by(|a, b| a.1.total_cmp(&b.1));
+        let current: Vec<f64> = frequencies.iter().map(|f| f.current_mhz).collect();
+        let current_stats = Self::calc_stats(&current);
+
+        Some(ThermalStats {
+            peak_temp_c: peak.map(|(_, temp)| *temp),
+            peak_sensor: peak.map(|(sensor, _)| sensor.clone()),
+            hottest_temp_c: Self::calc_stats(&hottest.iter().map(|(_, temp)| *temp).collect::<Vec<f64>>()),
+            max_freq_mhz: frequencies.iter().map(|f| f.max_mhz).fold(0.0, f64::max),
+            highest_freq_mhz: current_stats.max,
+            lowest_freq_mhz: current_stats.min,
+            avg_freq_mhz: current_stats.avg,
+            max_freq_drop_percent: Self::busy_core_freq_drops(snapshots).fold(0.0, f64::max),
+        })
+    }
+
+    // An idle core clocked down by the governor is saving power, not throttled, so only cores
+    // that were at least 80% busy in the same sample count
+    fn busy_core_freq_drops(snapshots: &[Snapshot]) -> impl Iterator<Item = f64> + '_ {
+        snapshots.iter().flat_map(|s| {
+            let cores = &s.metrics.cpu.cores;
+            s.metrics.thermal.frequencies.iter()
+                .filter(move |f| cores.iter().any(|c| c.core == f.core && c.usage_percent >= 80.0))
+                .map(|f| 100.0 - f.percent_of_max)
+        })
+    }
+
+    fn calc_watch_stats(snapshots: &[Snapshot]) -> Option<WatchStats> {
+        let samples: Vec<&ProcessTreeMetrics> = snapshots.iter()
+            .filter_map(|s| s.metrics.watched.as_ref())
+            .collect();
+        let (first, last) = (samples.first()?, samples.last()?);
+
+        let stats = |f: fn(&ProcessTreeMetrics) -> f64| {
+            Self::calc_stats(&samples.iter().map(|w| f(w)).collect::<Vec<f64>>())
+        };
+
+        Some(WatchStats {
+            root_pid: last.root_pid,
+            rss_kb: stats(|w| w.rss_kb as f64),
+            cpu_percent: stats(|w| w.cpu_percent),
+            threads: stats(|w| w.threads as f64),
+            fd_count: stats(|w| w.fd_count as f64),
+            max_process_count: samples.iter().map(|w| w.process_count).max().unwrap_or(0),
+            total_read_bytes: last.read_bytes.saturating_sub(first.read_bytes),
+            total_write_bytes: last.write_bytes.saturating_sub(first.write_bytes),
+        })
+    }
+
+    // How much of what the stressors claim actually shows up in the process, and what sampling costs
+    fn calc_self_observation(snapshots: &[Snapshot], duration_seconds: i64) -> SelfObservation {
+        let (first, last) = match (snapshots.first(), snapshots.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return SelfObservation::default(),
+        };
+        let (p0, p1) = (&first.metrics.self_process, &last.metrics.self_process);
+
+        // profiles that release memory can end below where they started; there is no share to report then
+        let leak_delta_bytes = last.stress.leak_total_bytes as i64 - first.stress.leak_total_bytes as i64;
+        let rss_growth_kb = p1.rss_kb as i64 - p0.rss_kb as i64;
+        let swap_growth_kb = p1.swap_kb as i64 - p0.swap_kb as i64;
+        let share_of_leak = |growth_kb: i64| if leak_delta_bytes > 0 {
+            (growth_kb as f64 * 1024.0 / leak_delta_bytes as f64) * 100.0
+        } else { 0.0 };
+
+        let process_cpu_secs = p1.cpu_time_secs - p0.cpu_time_secs;
+        let ops_delta = last.stress.cpu_total_ops
+            .saturating_sub(first.stress.cpu_total_ops);
+        let monitor_cpu_secs = p1.monitor_thread_cpu_secs - p0.monitor_thread_cpu_secs;
+
+        SelfObservation {
+            leak_claimed_bytes: last.stress.leak_total_bytes,
+            leak_peak_bytes: snapshots.iter().map(|s| s.stress.leak_total_bytes).max().unwrap_or(0),
+            leak_freed_bytes: last.stress.leak_freed_bytes,
+            rss_growth_kb,
+            swap_growth_kb,
+            leak_in_ram_percent: share_of_leak(rss_growth_kb),
+            leak_in_swap_percent: share_of_leak(swap_growth_kb),
+            peak_rss_kb: snapshots.iter().map(|s| s.metrics.self_process.rss_kb).max().unwrap_or(0),
+            peak_swap_kb: snapshots.iter().map(|s| s.metrics.self_process.swap_kb).max().unwrap_or(0),
+            peak_threads: snapshots.iter().map(|s| s.metrics.self_process.threads).max().unwrap_or(0),
+            peak_fd_count: snapshots.iter().map(|s| s.metrics.self_process.fd_count).max().unwrap_or(0),
+            cpu_total_ops: last.stress.cpu_total_ops,
+            cpu_ops_unit: last.stress.cpu_workers.first()
+                .map(|w| w.workload.unit().to_string())
+                .unwrap_or_default(),
+            process_cpu_secs,
+            ops_per_cpu_sec: if process_cpu_secs > 0.0 {
+                ops_delta as f64 / process_cpu_secs
+            } else { 0.0 },
+            monitor_cpu_secs,
+            monitor_cpu_percent: if duration_seconds > 0 {
+                monitor_cpu_secs / duration_seconds as f64 * 100.0
+            } else { 0.0 },
+            cpu_workers: last.stress.cpu_workers.clone(),
+            disk_write_bytes: last.stress.disk_write_bytes,
+            disk_read_bytes: last.stress.disk_read_bytes,
+            disk_fsyncs: last.stress.disk_fsyncs,
+        }
+    }
+
+    fn calc_numa_node_stats(snapshots: &[Snapshot]) -> Vec<NumaNodeStats> {
+        let mut nodes: Vec<u32> = snapshots.iter()
+            .flat_map(|s| s.metrics.numa_nodes.iter().map(|n| n.node))
+            .collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+
+        nodes.into_iter()
+            .map(|node| {
+                let samples: Vec<&NumaNode> = snapshots.iter()
+                    .filter_map(|s| s.metrics.numa_nodes.iter().find(|n| n.node == node))
+                    .collect();
+                let stats = |f: fn(&NumaNode) -> f64| {
+                    Self::calc_stats(&samples.iter().map(|n| f(n)).collect::<Vec<f64>>())
+                };
+                let total = |f: fn(&NumaNode) -> u64| match (samples.first(), samples.last()) {
+                    (Some(first), Some(last)) => f(last).saturating_sub(f(first)),
+                    _ => 0,
+                };
+                NumaNodeStats {
+                    node,
+                    mem_total_kb: samples.last().map(|n| n.mem_total_kb).unwrap_or(0),
+                    used_percent: stats(|n| n.used_percent),
+                    anon_pages_kb: stats(|n| n.anon_pages_kb as f64),
+                    total_numa_miss: total(|n| n.numa_miss),
+                    total_numa_foreign: total(|n| n.numa_foreign),
+                }
+            })
+            .collect()
+    }
+
+    // Processes come and go from the top tables, so each one is summarized over the samples it was in
+    fn calc_fd_stats(snapshots: &[Snapshot]) -> FdStats {
+        let mut keys: Vec<(u32, String)> = snapshots.iter()
+            .flat_map(|s| s.metrics.fds.processes.iter().map(|p| (p.pid, p.comm.clone())))
+            .collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut processes: Vec<ProcessFdStats> = keys.into_iter()
+            .map(|(pid, comm)| {
+                let samples: Vec<&ProcessFdUsage> = snapshots.iter()
+                    .filter_map(|s| s.metrics.fds.processes.iter().find(|p| p.pid == pid && p.comm == comm))
+                    .collect();
+                let first = samples.first().map(|p| p.fd_count).unwrap_or(0);
+                let last = samples.last().map(|p| p.fd_count).unwrap_or(0);
+                ProcessFdStats {
+                    peak_fd_count: samples.iter().map(|p| p.fd_count).max().unwrap_or(0),
+                    soft_limit: samples.last().and_then(|p| p.soft_limit),
+                    peak_used_percent: samples.iter().filter_map(|p| p.used_percent).reduce(f64::max),
+                    growth: last as i64 - first as i64,
+                    samples: samples.len(),
+                    pid,
+                    comm,
+                }
+            })
+            .collect();
+        processes.sort_by(|a, b| b.peak_used_percent.unwrap_or(0.0).total_cmp(&a.peak_used_percent.unwrap_or(0.0)));
+
+        FdStats {
+            system_used_percent: Self::calc_stats(&snapshots.iter().map(|s| s.metrics.fds.used_percent).collect::<Vec<f64>>()),
+            system_allocated: Self::calc_stats(&snapshots.iter().map(|s| s.metrics.fds.allocated as f64).collect::<Vec<f64>>()),
+            processes,
+        }
+    }
+
+    fn calc_protocol_stats(snapshots: &[Snapshot]) -> ProtocolStats {
+        let samples: Vec<&ProtocolMetrics> = snapshots.iter()
+            .filter_map(|s| s.metrics.protocols.as_ref())
+            .collect();
+        let (first, last) = match (samples.first(), samples.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return ProtocolStats::default(),
+        };
+        let stats = |f: fn(&ProtocolMetrics) -> f64| {
+            Self::calc_stats(&samples.iter().map(|p| f(p)).collect::<Vec<f64>>())
+        };
+        let total = |f: fn(&ProtocolMetrics) -> u64| f(last).saturating_sub(f(first));
+
+        ProtocolStats {
+            tcp_established: stats(|p| p.tcp.established as f64),
+            tcp_time_wait: stats(|p| p.tcp.time_wait as f64),
+            tcp_orphans: stats(|p| p.tcp.orphans as f64),
+            tcp_active_opens_per_sec: stats(|p| p.tcp.active_opens_per_sec),
+            tcp_passive_opens_per_sec: stats(|p| p.tcp.passive_opens_per_sec),
+            tcp_resets_per_sec: stats(|p| p.tcp.resets_per_sec),
+            tcp_retrans_percent: stats(|p| p.tcp.retrans_percent),
+            total_tcp_retrans_segs: total(|p| p.tcp.retrans_segs),
+            total_tcp_resets: total(|p| p.tcp.estab_resets + p.tcp.out_rsts),
+            total_tcp_listen_drops: total(|p| p.tcp.listen_drops),
+            udp_in_datagrams_per_sec: stats(|p| p.udp.in_datagrams_per_sec),
+            total_udp_rcvbuf_errors: total(|p| p.udp.rcvbuf_errors),
+            total_udp_in_errors: total(|p| p.udp.in_errors),
+        }
+    }
+
+    fn calc_interface_stats(snapshots: &[Snapshot]) -> Vec<InterfaceStats> {
+        let mut interfaces: Vec<String> = snapshots.iter()
+            .flat_map(|s| s.metrics.network.iter().map(|n| n.interface.clone()))
+            .collect();
+        interfaces.sort();
+        interfaces.dedup();
+
+        interfaces.into_iter()
+            .map(|interface| {
+                let samples: Vec<&InterfaceMetrics> = snapshots.iter()
+                    .filter_map(|s| s.metrics.network.iter().find(|n| n.interface == interface))
+                    .collect();
+                let stats = |f: fn(&InterfaceMetrics) -> f64| {
+                    Self::calc_stats(&samples.iter().map(|n| f(n)).collect::<Vec<f64>>())
+                };
+                InterfaceStats {
+                    rx_bytes_per_sec: stats(|n| n.rx_bytes_per_sec),
+                    tx_bytes_per_sec: stats(|n| n.tx_bytes_per_sec),
+                    errors_per_sec: stats(|n| n.errors_per_sec),
+                    drops_per_sec: stats(|n| n.drops_per_sec),
+                    counter_resets: samples.iter().filter(|n| n.counter_reset).count(),
+                    interface,
+                }
+            })
+            .collect()
+    }
+
+    fn calc_disk_stats(snapshots: &[Snapshot]) -> Vec<DiskStats> {
+        let mut devices: Vec<String> = snapshots.iter()
+            .flat_map(|s| s.metrics.disks.iter().map(|d| d.device.clone()))
+            .collect();
+        devices.sort();
+        devices.dedup();
+
+        devices.into_iter()
+            .map(|device| {
+                let samples: Vec<&DiskMetrics> = snapshots.iter()
+                    .filter_map(|s| s.metrics.disks.iter().find(|d| d.device == device))
+                    .collect();
+                let stats = |f: fn(&DiskMetrics) -> f64| {
+                    Self::calc_stats(&samples.iter().map(|d| f(d)).collect::<Vec<f64>>())
+                };
+                DiskStats {
+                    read_bytes_per_sec: stats(|d| d.read_bytes_per_sec),
+                    write_bytes_per_sec: stats(|d| d.write_bytes_per_sec),
+                    iops: stats(|d| d.read_iops + d.write_iops),
+                    latency_ms: stats(|d| d.read_latency_ms.max(d.write_latency_ms)),
+                    util_percent: stats(|d| d.util_percent),
+                    device,
+                }
+            })
+            .collect()
+    }
+
+    fn calc_core_stats(snapshots: &[Snapshot]) -> Vec<CoreStats> {
+        let mut core_ids: Vec<usize> = snapshots.iter()
+            .flat_map(|s| s.metrics.cpu.cores.iter().map(|c| c.core))
+            .collect();
+        core_ids.sort_unstable();
+        core_ids.dedup();
+
+        core_ids.into_iter()
+            .map(|core| {
+                let values: Vec<f64> = snapshots.iter()
+                    .filter_map(|s| s.metrics.cpu.cores.iter().find(|c| c.core == core))
+                    .map(|c| c.usage_percent)
+                    .collect();
+                CoreStats { core, usage: Self::calc_stats(&values) }
+            })
+            .collect()
+    }
+
     fn calc_stats(values: &[f64]) -> ResourceStats {
         if values.is_empty() {
             return ResourceStats { min: 0.0, max: 0.0, avg: 0.0, final_value: 0.0 };
@@ -226,7 +929,227 @@ impl FinalReport {
                  self.statistics.swap_stats.max,
                  self.statistics.swap_stats.avg,
                  self.statistics.swap_stats.final_value);
+        println!("-- IOWAIT:   min {:.1}% | max {:.1}% | avg {:.1}% | final {:.1}%",
+                 self.statistics.iowait_stats.min,
+                 self.statistics.iowait_stats.max,
+                 self.statistics.iowait_stats.avg,
+                 self.statistics.iowait_stats.final_value);
+        println!("-- STEAL:    min {:.1}% | max {:.1}% | avg {:.1}% | final {:.1}%",
+                 self.statistics.steal_stats.min,
+                 self.statistics.steal_stats.max,
+                 self.statistics.steal_stats.avg,
+                 self.statistics.steal_stats.final_value);
+        println!("-- MAJFLT/s: min {:.1} | max {:.1} | avg {:.1} | final {:.1}",
+                 self.statistics.major_fault_stats.min,
+                 self.statistics.major_fault_stats.max,
+                 self.statistics.major_fault_stats.avg,
+                 self.statistics.major_fault_stats.final_value);
+        println!("-- SWPIO/s:  min {:.1} | max {:.1} | avg {:.1} | final {:.1} | OOM kills: {}",
+                 self.statistics.swap_activity_stats.min,
+                 self.statistics.swap_activity_stats.max,
+                 self.statistics.swap_activity_stats.avg,
+                 self.statistics.swap_activity_stats.final_value,
+                 self.statistics.total_oom_kills);
+        println!("-- COMMIT:   min {:.1}% | max {:.1}% | avg {:.1}% | final {:.1}% of CommitLimit",
+                 self.statistics.commit_stats.min,
+                 self.statistics.commit_stats.max,
+                 self.statistics.commit_stats.avg,
+                 self.statistics.commit_stats.final_value);
+        println!("-- CTXT/s:   min {:.0} | max {:.0} | avg {:.0} | final {:.0} | forks/s max {:.1} avg {:.1}",
+                 self.statistics.context_switch_stats.min,
+                 self.statistics.context_switch_stats.max,
+                 self.statistics.context_switch_stats.avg,
+                 self.statistics.context_switch_stats.final_value,
+                 self.statistics.fork_stats.max,
+                 self.statistics.fork_stats.avg);
+        println!("-- BLOCKED:  min {:.0} | max {:.0} | avg {:.1} | final {:.0}",
+                 self.statistics.blocked_stats.min,
+                 self.statistics.blocked_stats.max,
+                 self.statistics.blocked_stats.avg,
+                 self.statistics.blocked_stats.final_value);
+        for core in &self.statistics.per_core_stats {
+            println!("   cpu{:<4}  min {:.1}% | max {:.1}% | avg {:.1}% | final {:.1}%",
+                     core.core,
+                     core.usage.min,
+                     core.usage.max,
+                     core.usage.avg,
+                     core.usage.final_value);
+        }
+        for disk in &self.statistics.per_disk_stats {
+            println!("-- DISK {}: read avg {:.1} MB/s (max {:.1}) | write avg {:.1} MB/s (max {:.1}) | iops max {:.0} | latency max {:.1} ms | util avg {:.1}% (max {:.1}%)",
+                     disk.device,
+                     disk.read_bytes_per_sec.avg / (1024.0 * 1024.0),
+                     disk.read_bytes_per_sec.max / (1024.0 * 1024.0),
+                     disk.write_bytes_per_sec.avg / (1024.0 * 1024.0),
+                     disk.write_bytes_per_sec.max / (1024.0 * 1024.0),
+                     disk.iops.max,
+                     disk.latency_ms.max,
+                     disk.util_percent.avg,
+                     disk.util_percent.max);
+        }
+        for iface in &self.statistics.per_interface_stats {
+            println!("-- NET {}: rx min {:.1} | max {:.1} | avg {:.1} KB/s | tx min {:.1} | max {:.1} | avg {:.1} KB/s | err max {:.1}/s | drop max {:.1}/s",
+                     iface.interface,
+                     iface.rx_bytes_per_sec.min / 1024.0,
+                     iface.rx_bytes_per_sec.max / 1024.0,
+                     iface.rx_bytes_per_sec.avg / 1024.0,
+                     iface.tx_bytes_per_sec.min / 1024.0,
+                     iface.tx_bytes_per_sec.max / 1024.0,
+                     iface.tx_bytes_per_sec.avg / 1024.0,
+                     iface.errors_per_sec.max,
+                     iface.drops_per_sec.max);
+        }
+        for node in &self.statistics.per_numa_node_stats {
+            println!("-- NUMA node{}: {} MB | used min {:.1}% | max {:.1}% | avg {:.1}% | final {:.1}% | anon max {:.0} MB | miss {} | foreign {}",
+                     node.node,
+                     node.mem_total_kb / 1024,
+                     node.used_percent.min,
+                     node.used_percent.max,
+                     node.used_percent.avg,
+                     node.used_percent.final_value,
+                     node.anon_pages_kb.max / 1024.0,
+                     node.total_numa_miss,
+                     node.total_numa_foreign);
+        }
+        if !self.statistics.leak_node_bytes.is_empty() {
+            let nodes: Vec<String> = self.statistics.leak_node_bytes.iter()
+                .map(|n| format!("node{} {:.1} MB", n.node, n.bytes as f64 / (1024.0 * 1024.0)))
+                .collect();
+            println!("-- LEAK NODES: {}", nodes.join(" | "));
+        }
+        let fs = &self.statistics.fd_stats;
+        println!("-- FDS:      system max {:.0} ({:.2}%) | avg {:.0} | final {:.0}",
+                 fs.system_allocated.max,
+                 fs.system_used_percent.max,
+                 fs.system_allocated.avg,
+                 fs.system_allocated.final_value);
+        for p in fs.processes.iter().take(5) {
+            println!("   {:>7} {:<16} peak {} fds of {} ({}) | growth {:+} over {} samples",
+                     p.pid, p.comm, p.peak_fd_count,
+                     p.soft_limit.map(|l| l.to_string()).unwrap_or_else(|| "unlimited".into()),
+                     p.peak_used_percent.map(|pct| format!("{:.1}%", pct)).unwrap_or_else(|| "-".into()),
+                     p.growth, p.samples);
+        }
+        let ps = &self.statistics.protocol_stats;
+        println!("-- TCP:      estab max {:.0} avg {:.1} | tw max {:.0} | orphan max {:.0} | opens avg {:.1}/s active, {:.1}/s passive | retrans max {:.2}% ({} segs) | resets {} | listen drops {}",
+                 ps.tcp_established.max,
+                 ps.tcp_established.avg,
+                 ps.tcp_time_wait.max,
+                 ps.tcp_orphans.max,
+                 ps.tcp_active_opens_per_sec.avg,
+                 ps.tcp_passive_opens_per_sec.avg,
+                 ps.tcp_retrans_percent.max,
+                 ps.total_tcp_retrans_segs,
+                 ps.total_tcp_resets,
+                 ps.total_tcp_listen_drops);
+        println!("-- UDP:      in avg {:.0}/s max {:.0}/s | errors {} (rcvbuf {})",
+                 ps.udp_in_datagrams_per_sec.avg,
+                 ps.udp_in_datagrams_per_sec.max,
+                 ps.total_udp_in_errors,
+                 ps.total_udp_rcvbuf_errors);
+        if let Some(w) = &self.statistics.watch_stats {
+            println!("-- WATCH pid {}: rss max {:.1} MB avg {:.1} MB final {:.1} MB | cpu max {:.1}% avg {:.1}% | threads max {:.0} | fds max {:.0} | procs max {} | io r {:.1} MB w {:.1} MB",
+                     w.root_pid,
+                     w.rss_kb.max / 1024.0,
+                     w.rss_kb.avg / 1024.0,
+                     w.rss_kb.final_value / 1024.0,
+                     w.cpu_percent.max,
+                     w.cpu_percent.avg,
+                     w.threads.max,
+                     w.fd_count.max,
+                     w.max_process_count,
+                     w.total_read_bytes as f64 / (1024.0 * 1024.0),
+                     w.total_write_bytes as f64 / (1024.0 * 1024.0));
+        }
+        if let Some(l) = &self.statistics.leak_analysis {
+            println!("-- LEAK pid {}: {} | trend {:.1} MB/h (R² {:.2}) over {} samples | anon {:+.1} MB | file {:+.1} MB | shmem {:+.1} MB | swap {:+.1} MB",
+                     l.pid,
+                     if l.suspected { "SUSPECTED" } else { "not detected" },
+                     l.leak_mb_per_hour, l.r_squared, l.samples,
+                     l.anon_growth_kb as f64 / 1024.0,
+                     l.file_growth_kb as f64 / 1024.0,
+                     l.shmem_growth_kb as f64 / 1024.0,
+                     l.swap_growth_kb as f64 / 1024.0);
+        }
+        if let Some(t) = &self.statistics.thermal_stats {
+            let peak = match (t.peak_temp_c, &t.peak_sensor) {
+                (Some(temp), Some(sensor)) => format!("peak {:.1}°C ({}) | avg hottest {:.1}°C", temp, sensor, t.hottest_temp_c.avg),
+                _ => "no temperature sensors".into(),
+            };
+            println!("-- THERMAL:  {} | freq {:.0}-{:.0} MHz avg {:.0} (hw max {:.0}) | max drop {:.1}% below max on a busy core",
+                     peak,
+                     t.lowest_freq_mhz,
+                     t.highest_freq_mhz,
+                     t.avg_freq_mhz,
+                     t.max_freq_mhz,
+                     t.max_freq_drop_percent);
+        }
+        let so = &self.statistics.self_observation;
+        println!("-- SELF:     leak claimed {:.1} MB (peak {:.1} MB, freed {:.1} MB) | rss {:+.1} MB ({:.0}% of leak) | swap {:+.1} MB ({:.0}% of leak)",
+                 so.leak_claimed_bytes as f64 / (1024.0 * 1024.0),
+                 so.leak_peak_bytes as f64 / (1024.0 * 1024.0),
+                 so.leak_freed_bytes as f64 / (1024.0 * 1024.0),
+                 so.rss_growth_kb as f64 / 1024.0,
+                 so.leak_in_ram_percent,
+                 so.swap_growth_kb as f64 / 1024.0,
+                 so.leak_in_swap_percent);
+        println!("             peak rss {:.1} MB | peak threads {} | peak fds {} | cpu {:.1}s for {} {} | monitor thread {:.2}s ({:.2}%)",
+                 so.peak_rss_kb as f64 / 1024.0,
+                 so.peak_threads,
+                 so.peak_fd_count,
+                 so.process_cpu_secs,
+                 so.cpu_total_ops,
+                 so.cpu_ops_unit,
+                 so.monitor_cpu_secs,
+                 so.monitor_cpu_percent);
+        for w in &so.cpu_workers {
+            println!("             cpu worker #{}: {:.1}% achieved of {}% target ({:.1}s cpu over {:.1}s) | {:?} {:.3e} {}/cpu-s",
+                     w.worker, w.achieved_percent, w.target_percent, w.cpu_secs, w.wall_secs,
+                     w.workload, w.ops_per_cpu_sec, w.workload.unit());
+        }
+        if so.disk_write_bytes > 0 || so.disk_read_bytes > 0 {
+            println!("             disk stress wrote {:.1} MB | read {:.1} MB | {} fsyncs",
+                     so.disk_write_bytes as f64 / (1024.0 * 1024.0),
+                     so.disk_read_bytes as f64 / (1024.0 * 1024.0),
+                     so.disk_fsyncs);
+        }
         println!("Critical events: {} | Warnings: {}",
                  self.summary.critical_issues, self.summary.warnings);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::Collector;
+    use crate::paths::Fixture;
+
+    fn stat(cpu0: &str, cpu1: &str) -> String {
+        format!("cpu  0 0 0 0 0 0 0 0 0 0\ncpu0 {} 0 0 0 0 0 0\ncpu1 {} 0 0 0 0 0 0\n", cpu0, cpu1)
+    }
+
+    #[test]
+    fn frequency_drop_only_counts_busy_cores() {
+        let fixture = Fixture::new("report-thermal", &[
+            ("proc/meminfo", "MemTotal: 1000 kB\nMemFree: 400 kB\nMemAvailable: 600 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n"),
+            ("proc/loadavg", "0.50 0.40 0.30 1/100 42\n"),
+            ("proc/stat", &stat("0 0 0 0", "0 0 0 0")),
+        ]);
+        // cpu0 runs at 60% of its maximum while busy, idle cpu1 is parked at 20%
+        for (cpu, khz) in [("cpu0", "1800000"), ("cpu1", "600000")] {
+            fixture.write(&format!("sys/devices/system/cpu/{}/cpufreq/scaling_cur_freq", cpu), khz);
+            fixture.write(&format!("sys/devices/system/cpu/{}/cpufreq/cpuinfo_max_freq", cpu), "3000000");
+        }
+
+        let mut collector = Collector::for_fixture(&fixture);
+        let first = collector.collect().unwrap();
+        // cpu0: 900 of 1000 ticks busy, cpu1: 10 of 1000
+        fixture.write("proc/stat", &stat("900 0 0 100", "10 0 0 990"));
+        let second = collector.collect().unwrap();
+
+        let snapshots = vec![Snapshot::new(0, first), Snapshot::new(1, second)];
+        let stats = FinalReport::calc_thermal_stats(&snapshots).unwrap();
+        assert!((stats.max_freq_drop_percent - 40.0).abs() < 1e-9, "{}", stats.max_freq_drop_percent);
+        assert_eq!(stats.lowest_freq_mhz, 600.0);
+    }
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::error::MonitorError;
+use crate::monitor::CounterSet;
+use crate::paths::{self, HostPaths};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProtocolMetrics {
+    pub sockets_used: u64,
+    pub tcp: TcpMetrics,
+    pub udp: UdpMetrics,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TcpMetrics {
+    pub active_opens: u64,
+    pub passive_opens: u64,
+    pub attempt_fails: u64,
+    pub estab_resets: u64,
+    pub out_rsts: u64,
+    pub in_segs: u64,
+    pub out_segs: u64,
+    pub retrans_segs: u64,
+    pub in_errs: u64,
+    pub listen_overflows: u64,
+    pub listen_drops: u64,
+    pub timeouts: u64,
+    pub established: u64,
+    pub in_use: u64,
+    pub orphans: u64,
+    pub time_wait: u64,
+    pub allocated: u64,
+    pub mem_pages: u64,
+    pub active_opens_per_sec: f64,
+    pub passive_opens_per_sec: f64,
+    pub resets_per_sec: f64,
+    pub retrans_segs_per_sec: f64,
+    pub retrans_percent: f64,
+    pub listen_drops_per_sec: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UdpMetrics {
+    pub in_datagrams: u64,
+    pub out_datagrams: u64,
+    pub no_ports: u64,
+    pub in_errors: u64,
+    pub rcvbuf_errors: u64,
+    pub sndbuf_errors: u64,
+    pub in_use: u64,
+    pub mem_pages: u64,
+    pub in_datagrams_per_sec: f64,
+    pub out_datagrams_per_sec: f64,
+    pub in_errors_per_sec: f64,
+    pub rcvbuf_errors_per_sec: f64,
+}
+
+pub fn collect_protocol_metrics(
+    paths: &HostPaths,
+    counters: &mut CounterSet,
+) -> Result<ProtocolMetrics, MonitorError> {
+    let mut stats = parse_header_tables(&paths::read(&paths.proc("net/snmp"))?);
+    // TcpExt lives in netstat and some kernels or namespaces don't expose it
+    if let Ok(content) = paths::read(&paths.proc("net/netstat")) {
+        stats.extend(parse_header_tables(&content));
+    }
+    let sockstat = paths::read(&paths.proc("net/sockstat"))
+        .map(|content| parse_sockstat(&content))
+        .unwrap_or_default();
+
+    let get = |key: &str| stats.get(key).copied().unwrap_or(0);
+    let gauge = |key: &str| sockstat.get(key).copied().unwrap_or(0);
+
+    let mut tcp = TcpMetrics {
+        active_opens: get("Tcp.ActiveOpens"),
+        passive_opens: get("Tcp.PassiveOpens"),
+        attempt_fails: get("Tcp.AttemptFails"),
+        estab_resets: get("Tcp.EstabResets"),
+        out_rsts: get("Tcp.OutRsts"),
+        in_segs: get("Tcp.InSegs"),
+        out_segs: get("Tcp.OutSegs"),
+        retrans_segs: get("Tcp.RetransSegs"),
+        in_errs: get("Tcp.InErrs"),
+        listen_overflows: get("TcpExt.ListenOverflows"),
+        listen_drops: get("TcpExt.ListenDrops"),
+        timeouts: get("TcpExt.TCPTimeouts"),
+        established: get("Tcp.CurrEstab"),
+        in_use: gauge("TCP.inuse"),
+        orphans: gauge("TCP.orphan"),
+        time_wait: gauge("TCP.tw"),
+        allocated: gauge("TCP.alloc"),
+        mem_pages: gauge("TCP.mem"),
+        ..TcpMetrics::default()
+    };
+    tcp.active_opens_per_sec = counters.rate("tcp.active_opens", tcp.active_opens);
+    tcp.passive_opens_per_sec = counters.rate("tcp.passive_opens", tcp.passive_opens);
+    tcp.resets_per_sec = counters.rate("tcp.resets", tcp.estab_resets + tcp.out_rsts);
+    tcp.listen_drops_per_sec = counters.rate("tcp.listen_drops", tcp.listen_drops);
+
+    // retransmits as a share of segments sent in the same interval
+    let retrans = counters.delta("tcp.retrans_segs", tcp.retrans_segs).unwrap_or(0);
+    let sent = counters.delta("tcp.out_segs", tcp.out_segs).unwrap_or(0);
+    tcp.retrans_segs_per_sec = counters.per_sec(retrans);
+    tcp.retrans_percent = if sent > 0 {
+        (retrans as f64 / sent as f64) * 100.0
+    } else { 0.0 };
+
+    let mut udp = UdpMetrics {
+        in_datagrams: get("Udp.InDatagrams"),
+        out_datagrams: get("Udp.OutDatagrams"),
+        no_ports: get("Udp.NoPorts"),
+        in_errors: get("Udp.InErrors"),
+        rcvbuf_errors: get("Udp.RcvbufErrors"),
+        sndbuf_errors: get("Udp.SndbufErrors"),
+        in_use: gauge("UDP.inuse"),
+        mem_pages: gauge("UDP.mem"),
+        ..UdpMetrics::default()
+    };
+    udp.in_datagrams_per_sec = counters.rate("udp.in_datagrams", udp.in_datagrams);
+    udp.out_datagrams_per_sec = counters.rate("udp.out_datagrams", udp.out_datagrams);
+    udp.in_errors_per_sec = counters.rate("udp.in_errors", udp.in_errors);
+    udp.rcvbuf_errors_per_sec = counters.rate("udp.rcvbuf_errors", udp.rcvbuf_errors);
+
+    Ok(ProtocolMetrics {
+        sockets_used: gauge("sockets.used"),
+        tcp,
+        udp,
+    })
+}
+
+// snmp and netstat come in line pairs: "Tcp: ActiveOpens PassiveOpens ..." then "Tcp: 8 8 ...".
+// Keys come out as "Tcp.ActiveOpens"; signed fields like MaxConn (-1) are left out.
+fn parse_header_tables(content: &str) -> HashMap<String, u64> {
+    let mut values = HashMap::new();
+    let lines: Vec<&str> = content.lines().collect();
+
+    for pair in lines.chunks(2) {
+        let (header, data) = match pair {
+            [header, data] => (header, data),
+            _ => continue,
+        };
+        let (prefix, names) = match header.split_once(':') {
+            Some(split) => split,
+            None => continue,
+        };
+        let numbers = match data.split_once(':') {
+            Some((data_prefix, numbers)) if data_prefix == prefix => numbers,
+            _ => continue,
+        };
+
+        for (name, value) in names.split_whitespace().zip(numbers.split_whitespace()) {
+            if let Ok(value) = value.parse::<u64>() {
+                values.insert(format!("{}.{}", prefix, name), value);
+            }
+        }
+    }
+
+    values
+}
+
+// "TCP: inuse 4 orphan 0 tw 0 alloc 4 mem 0" -> TCP.inuse, TCP.orphan, ...
+fn parse_sockstat(content: &str) -> HashMap<String, u64> {
+    let mut values = HashMap::new();
+
+    for line in content.lines() {
+        let (prefix, fields) = match line.split_once(':') {
+            Some(split) => split,
+            None => continue,
+        };
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        for pair in fields.chunks(2) {
+            if let [name, value] = pair {
+                if let Ok(value) = value.parse::<u64>() {
+                    values.insert(format!("{}.{}", prefix, name), value);
+                }
+            }
+        }
+    }
+
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::Fixture;
+
+    const SNMP: &str = "\
+Ip: Forwarding DefaultTTL InReceives
+Ip: 1 64 1000
+Tcp: RtoAlgorithm RtoMin RtoMax MaxConn ActiveOpens PassiveOpens AttemptFails EstabResets CurrEstab InSegs OutSegs RetransSegs InErrs OutRsts
+Tcp: 1 200 120000 -1 50 20 3 4 7 10000 8000 80 0 6
+Udp: InDatagrams NoPorts InErrors OutDatagrams RcvbufErrors SndbufErrors
+Udp: 500 2 1 400 1 0
+";
+
+    const SOCKSTAT: &str = "\
+sockets: used 120
+TCP: inuse 9 orphan 1 tw 5 alloc 12 mem 3
+UDP: inuse 2 mem 1
+";
+
+    #[test]
+    fn header_tables_pair_names_with_values() {
+        let values = parse_header_tables(SNMP);
+        assert_eq!(values.get("Tcp.ActiveOpens"), Some(&50));
+        assert_eq!(values.get("Tcp.OutRsts"), Some(&6));
+        assert_eq!(values.get("Udp.RcvbufErrors"), Some(&1));
+        assert_eq!(values.get("Ip.InReceives"), Some(&1000));
+        // -1 doesn't fit a counter and is left out
+        assert_eq!(values.get("Tcp.MaxConn"), None);
+    }
+
+    #[test]
+    fn header_tables_skip_mismatched_pairs() {
+        let values = parse_header_tables("Tcp: ActiveOpens\nUdp: 5\n");
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn sockstat_reads_name_value_pairs() {
+        let values = parse_sockstat(SOCKSTAT);
+        assert_eq!(values.get("sockets.used"), Some(&120));
+        assert_eq!(values.get("TCP.tw"), Some(&5));
+        assert_eq!(values.get("UDP.mem"), Some(&1));
+    }
+
+    #[test]
+    fn collects_without_netstat() {
+        let fixture = Fixture::new("sockets", &[("proc/net/snmp", SNMP), ("proc/net/sockstat", SOCKSTAT)]);
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+        let p = collect_protocol_metrics(&fixture.paths, &mut counters).unwrap();
+
+        assert_eq!(p.sockets_used, 120);
+        assert_eq!((p.tcp.active_opens, p.tcp.established, p.tcp.time_wait), (50, 7, 5));
+        assert_eq!(p.tcp.listen_drops, 0);
+        assert_eq!((p.udp.in_datagrams, p.udp.no_ports, p.udp.in_use), (500, 2, 2));
+    }
+
+    #[test]
+    fn retransmits_are_a_share_of_segments_sent() {
+        let fixture = Fixture::new("sockets-retrans", &[("proc/net/snmp", SNMP)]);
+        let mut counters = CounterSet::default();
+        counters.begin_sample();
+        collect_protocol_metrics(&fixture.paths, &mut counters).unwrap();
+
+        // 1000 more segments out, 20 of them retransmits
+        fixture.write("proc/net/snmp", &SNMP.replace("10000 8000 80", "10000 9000 100"));
+        counters.begin_sample();
+        let p = collect_protocol_metrics(&fixture.paths, &mut counters).unwrap();
+        assert_eq!(p.tcp.retrans_percent, 2.0);
+    }
+}
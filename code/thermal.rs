@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::paths::HostPaths;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ThermalMetrics {
+    pub zones: Vec<ThermalZone>,
+    pub sensors: Vec<TemperatureSensor>,
+    pub frequencies: Vec<CoreFrequency>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThermalZone {
+    pub zone: String,
+    pub zone_type: String,
+    pub temp_c: f64,
+    pub critical_c: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemperatureSensor {
+    pub chip: String,
+    pub label: String,
+    pub temp_c: f64,
+    pub max_c: Option<f64>,
+    pub critical_c: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CoreFrequency {
+    pub core: usize,
+    pub current_mhz: f64,
+    pub min_mhz: f64,
+    pub max_mhz: f64,
+    pub percent_of_max: f64,
+}
+
+impl ThermalMetrics {
+    // every temperature with its name and the limit it is judged against (critical, else max)
+    pub fn readings(&self) -> Vec<(String, f64, Option<f64>)> {
+        let zones = self.zones.iter()
+            .map(|z| (z.zone_type.clone(), z.temp_c, z.critical_c));
+        let sensors = self.sensors.iter()
+            .map(|s| (format!("{}/{}", s.chip, s.label), s.temp_c, s.critical_c.or(s.max_c)));
+        zones.chain(sensors).collect()
+    }
+
+    pub fn hottest(&self) -> Option<(String, f64)> {
+        self.readings().into_iter()
+            .map(|(name, temp, _)| (name, temp))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    pub fn lowest_frequency(&self) -> Option<&CoreFrequency> {
+        self.frequencies.iter().min_by(|a, b| a.percent_of_max.total_cmp(&b.percent_of_max))
+    }
+}
+
+// VMs and containers usually expose none of these nodes, which just leaves the lists empty
+pub fn collect_thermal_metrics(paths: &HostPaths) -> ThermalMetrics {
+    ThermalMetrics {
+        zones: collect_zones(&paths.sys("class/thermal")),
+        sensors: collect_hwmon(&paths.sys("class/hwmon")),
+        frequencies: collect_frequencies(&paths.sys("devices/system/cpu")),
+    }
+}
+
+fn collect_zones(dir: &Path) -> Vec<ThermalZone> {
+    numbered_entries(dir, "thermal_zone")
+        .into_iter()
+        .filter_map(|(_, zone_dir)| {
+            let temp_c = read_millidegrees(&zone_dir.join("temp"))?;
+            let zone_type = read_trimmed(&zone_dir.join("type")).unwrap_or_else(|| "unknown".into());
+
+            // trip points are numbered from 0 and the critical one can be any of them
+            let critical_c = (0..)
+                .map_while(|i| {
+                    let trip_type = read_trimmed(&zone_dir.join(format!("trip_point_{}_type", i)))?;
+                    Some((trip_type, zone_dir.join(format!("trip_point_{}_temp", i))))
+                })
+                .find(|(trip_type, _)| trip_type == "critical")
+                .and_then(|(_, temp_path)| read_millidegrees(&temp_path));
+
+            Some(ThermalZone {
+                zone: zone_dir.file_name()?.to_string_lossy().into_owned(),
+                zone_type,
+                temp_c,
+                critical_c,
+            })
+        })
+        .collect()
+}
+
+fn collect_hwmon(dir: &Path) -> Vec<TemperatureSensor> {
+    let mut sensors = Vec::new();
+
+    for (_, chip_dir) in numbered_entries(dir, "hwmon") {
+        let chip = read_trimmed(&chip_dir.join("name"))
+            .unwrap_or_else(|| chip_dir.file_name().unwrap_or_default().to_string_lossy().into_owned());
+
+        for (index, _) in numbered_entries(&chip_dir, "temp") {
+            let file = |suffix: &str| chip_dir.join(format!("temp{}_{}", index, suffix));
+            let temp_c = match read_millidegrees(&file("input")) {
+                Some(temp_c) => temp_c,
+                None => continue,
+            };
+
+            sensors.push(TemperatureSensor {
+                chip: chip.clone(),
+                label: read_trimmed(&file("label")).unwrap_or_else(|| format!("temp{}", index)),
+                temp_c,
+                max_c: read_millidegrees(&file("max")),
+                critical_c: read_millidegrees(&file("crit")),
+            });
+        }
+    }
+
+    sensors
+}
+
+fn collect_frequencies(dir: &Path) -> Vec<CoreFrequency> {
+    numbered_entries(dir, "cpu")
+        .into_iter()
+        .filter_map(|(core, cpu_dir)| {
+            let freq_dir = cpu_dir.join("cpufreq");
+            let khz = |names: &[&str]| names.iter()
+                .find_map(|name| read_trimmed(&freq_dir.join(name))?.parse::<f64>().ok());
+
+            let current_mhz = khz(&["scaling_cur_freq", "cpuinfo_cur_freq"])? / 1000.0;
+            // hardware limits first: scaling_max_freq is itself lowered by some thermal drivers
+            let min_mhz = khz(&["cpuinfo_min_freq", "scaling_min_freq"]).unwrap_or(0.0) / 1000.0;
+            let max_mhz = khz(&["cpuinfo_max_freq", "scaling_max_freq"]).unwrap_or(0.0) / 1000.0;
+
+            Some(CoreFrequency {
+                core,
+                current_mhz,
+                min_mhz,
+                max_mhz,
+                percent_of_max: if max_mhz > 0.0 { current_mhz / max_mhz * 100.0 } else { 100.0 },
+            })
+        })
+        .collect()
+}
+
+// entries named <prefix><number>, sorted by number: thermal_zone0, hwmon3, cpu12, temp1_input
+fn numbered_entries(dir: &Path, prefix: &str) -> Vec<(usize, PathBuf)> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut numbered: Vec<(usize, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let rest = name.strip_prefix(prefix)?;
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            // "temp1_input" names the sensor, "cpufreq" or "cpuidle" are not cores
+            let suffix = &rest[digits.len()..];
+            if digits.is_empty() || !(suffix.is_empty() || suffix == "_input") {
+                return None;
+            }
+            Some((digits.parse().ok()?, entry.path()))
+        })
+        .collect();
+    numbered.sort_by_key(|(index, _)| *index);
+    numbered.dedup_by_key(|(index, _)| *index);
+    numbered
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|content| content.trim().to_string())
+}
+
+fn read_millidegrees(path: &Path) -> Option<f64> {
+    read_trimmed(path)?.parse::<i64>().ok().map(|m| m as f64 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::Fixture;
+
+    #[test]
+    fn reads_zones_sensors_and_frequencies_from_the_sys_root() {
+        let fixture = Fixture::new("thermal", &[
+            ("sys/class/thermal/thermal_zone0/type", "acpitz\n"),
+            ("sys/class/thermal/thermal_zone0/temp", "45500\n"),
+            ("sys/class/thermal/thermal_zone0/trip_point_0_type", "passive\n"),
+            ("sys/class/thermal/thermal_zone0/trip_point_0_temp", "80000\n"),
+            ("sys/class/thermal/thermal_zone0/trip_point_1_type", "critical\n"),
+            ("sys/class/thermal/thermal_zone0/trip_point_1_temp", "105000\n"),
+            ("sys/class/thermal/cooling_device0/type", "fan\n"),
+            ("sys/class/hwmon/hwmon2/name", "nvme\n"),
+            ("sys/class/hwmon/hwmon2/temp1_input", "38000\n"),
+            ("sys/class/hwmon/hwmon2/temp1_label", "Composite\n"),
+            ("sys/class/hwmon/hwmon2/temp1_max", "81000\n"),
+            ("sys/class/hwmon/hwmon2/temp2_input", "41000\n"),
+            ("sys/devices/system/cpu/cpu0/cpufreq/scaling_cur_freq", "1200000\n"),
+            ("sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq", "2400000\n"),
+            ("sys/devices/system/cpu/cpufreq/boost", "1\n"),
+        ]);
+        let metrics = collect_thermal_metrics(&fixture.paths);
+
+        assert_eq!(metrics.zones.len(), 1);
+        assert_eq!((metrics.zones[0].zone.as_str(), metrics.zones[0].zone_type.as_str()), ("thermal_zone0", "acpitz"));
+        assert_eq!((metrics.zones[0].temp_c, metrics.zones[0].critical_c), (45.5, Some(105.0)));
+
+        let labels: Vec<&str> = metrics.sensors.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(labels, ["Composite", "temp2"]);
+        assert_eq!(metrics.sensors[0].max_c, Some(81.0));
+
+        // cpufreq is not a core
+        assert_eq!(metrics.frequencies.len(), 1);
+        assert_eq!((metrics.frequencies[0].current_mhz, metrics.frequencies[0].percent_of_max), (1200.0, 50.0));
+
+        assert_eq!(metrics.hottest(), Some(("acpitz".to_string(), 45.5)));
+        assert_eq!(metrics.readings()[1], ("nvme/Composite".to_string(), 38.0, Some(81.0)));
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use crate::error::MonitorError;
+use crate::monitor::CounterSet;
+use crate::paths::{self, HostPaths};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VmstatMetrics {
+    pub pgfault: u64,
+    pub pgmajfault: u64,
+    pub pswpin: u64,
+    pub pswpout: u64,
+    pub pgpgin: u64,
+    pub pgpgout: u64,
+    pub pgscan: u64,
+    pub pgsteal: u64,
+    pub oom_kill: u64,
+    pub pgfault_per_sec: f64,
+    pub pgmajfault_per_sec: f64,
+    pub pswpin_per_sec: f64,
+    pub pswpout_per_sec: f64,
+    pub pgpgin_per_sec: f64,
+    pub pgpgout_per_sec: f64,
+    pub pgscan_per_sec: f64,
+    pub pgsteal_per_sec: f64,
+    pub oom_kill_delta: u64,
+}
+
+pub fn collect_vmstat_metrics(
+    paths: &HostPaths,
+    counters: &mut CounterSet,
+) -> Result<VmstatMetrics, MonitorError> {
+    let content = paths::read(&paths.proc("vmstat"))?;
+
+    let mut m = VmstatMetrics::default();
+
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 2 { continue; }
+        let value = parts[1].parse::<u64>().unwrap_or(0);
+
+        match parts[0] {
+            "pgfault" => m.pgfault = value,
+            "pgmajfault" => m.pgmajfault = value,
+            "pswpin" => m.pswpin = value,
+            "pswpout" => m.pswpout = value,
+            "pgpgin" => m.pgpgin = value,
+            "pgpgout" => m.pgpgout = value,
+            "pgscan_kswapd" | "pgscan_direct" => m.pgscan += value,
+            "pgsteal_kswapd" | "pgsteal_direct" => m.pgsteal += value,
+            "oom_kill" => m.oom_kill = value,
+            _ => {}
+        }
+    }
+
+    m.pgfault_per_sec = counters.rate("vmstat.pgfault", m.pgfault);
+    m.pgmajfault_per_sec = counters.rate("vmstat.pgmajfault", m.pgmajfault);
+    m.pswpin_per_sec = counters.rate("vmstat.pswpin", m.pswpin);
+    m.pswpout_per_sec = counters.rate("vmstat.pswpout", m.pswpout);
+    m.pgpgin_per_sec = counters.rate("vmstat.pgpgin", m.pgpgin);
+    m.pgpgout_per_sec = counters.rate("vmstat.pgpgout", m.pgpgout);
+    m.pgscan_per_sec = counters.rate("vmstat.pgscan", m.pgscan);
+    m.pgsteal_per_sec = counters.rate("vmstat.pgsteal", m.pgsteal);
+    m.oom_kill_delta = counters.delta("vmstat.oom_kill", m.oom_kill).unwrap_or(0);
+
+    Ok(m)
+}
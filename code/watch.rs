@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::process::{Child, Command};
+use crate::config::Config;
+use crate::error::MonitorError;
+use crate::monitor::CounterSet;
+use crate::paths::HostPaths;
+use crate::process::{self, ProcStat};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessTreeMetrics {
+    pub root_pid: u32,
+    pub process_count: usize,
+    pub rss_kb: u64,
+    pub swap_kb: u64,
+    pub cpu_percent: f64,
+    pub threads: u64,
+    pub fd_count: u64,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub processes: Vec<WatchedProcess>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WatchedProcess {
+    pub pid: u32,
+    pub ppid: u32,
+    pub comm: String,
+    pub state: String,
+    pub rss_kb: u64,
+    pub swap_kb: u64,
+    pub cpu_percent: f64,
+    pub threads: u64,
+    pub fd_count: u64,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+}
+
+pub struct WatchTarget {
+    pub pid: u32,
+    paths: HostPaths,
+    child: Option<Child>,
+    exit_status: Option<String>,
+}
+
+impl WatchTarget {
+    pub fn from_config(config: &Config) -> Result<Option<Self>, MonitorError> {
+        let paths = HostPaths::new(&config.proc_root, &config.sys_root);
+
+        if let Some((program, args)) = config.command.split_first() {
+            let child = Command::new(program)
+                .args(args)
+                .spawn()
+                .map_err(|e| MonitorError::CommandError(format!("{}: {}", program, e)))?;
+            return Ok(Some(WatchTarget { pid: child.id(), paths, child: Some(child), exit_status: None }));
+        }
+
+        if let Some(pid) = config.pid {
+            let dir = paths.proc(pid.to_string());
+            if !dir.exists() {
+                return Err(MonitorError::FileRead(format!("{}: no such process", dir.display())));
+            }
+            return Ok(Some(WatchTarget { pid, paths, child: None, exit_status: None }));
+        }
+
+        Ok(None)
+    }
+
+    pub fn is_alive(&mut self) -> bool {
+        if self.exit_status.is_some() {
+            return false;
+        }
+
+        match &mut self.child {
+            Some(child) => match child.try_wait() {
+                Ok(Some(status)) => {
+                    self.exit_status = Some(status.to_string());
+                    false
+                }
+                Ok(None) => true,
+                Err(e) => {
+                    self.exit_status = Some(format!("unknown ({})", e));
+                    false
+                }
+            },
+            // not our child, so it can't be reaped; a zombie counts as exited
+            None => match process::read_proc_stat(&self.paths, self.pid) {
+                Ok(stat) if stat.state != 'Z' => true,
+                _ => {
+                    self.exit_status = Some("exited".into());
+                    false
+                }
+            },
+        }
+    }
+
+    pub fn exit_status(&self) -> Option<&str> {
+        self.exit_status.as_deref()
+    }
+}
+
+pub fn collect_tree_metrics(
+    paths: &HostPaths,
+    root_pid: u32,
+    counters: &mut CounterSet,
+) -> Result<Option<ProcessTreeMetrics>, MonitorError> {
+    let stats: Vec<ProcStat> = process::list_pids(paths)?
+        .into_iter()
+        .filter_map(|pid| process::read_proc_stat(paths, pid).ok())
+        .collect();
+
+    if !stats.iter().any(|s| s.pid == root_pid) {
+        return Ok(None);
+    }
+
+    let mut children: HashMap<u32, Vec<&ProcStat>> = HashMap::new();
+    for stat in &stats {
+        children.entry(stat.ppid).or_default().push(stat);
+    }
+
+    let mut tree: Vec<&ProcStat> = stats.iter().filter(|s| s.pid == root_pid).collect();
+    let mut i = 0;
+    while i < tree.len() {
+        if let Some(kids) = children.get(&tree[i].pid) {
+            tree.extend(kids.iter().copied());
+        }
+        i += 1;
+    }
+
+    let ticks_per_sec = process::clock_ticks_per_sec();
+    let page_kb = process::page_size_kb();
+    let mut processes = Vec::new();
+
+    for stat in tree {
+        let status = process::read_proc_status(paths, stat.pid).unwrap_or_default();
+        // /proc/[pid]/io needs ptrace access; without it I/O is reported as zero
+        let (read_bytes, write_bytes) = read_proc_io(paths, stat.pid).unwrap_or((0, 0));
+
+        processes.push(WatchedProcess {
+            pid: stat.pid,
+            ppid: stat.ppid,
+            comm: stat.comm.clone(),
+            state: stat.state.to_string(),
+            rss_kb: process::status_u64(&status, "VmRSS").unwrap_or(stat.rss_pages * page_kb),
+            swap_kb: process::status_u64(&status, "VmSwap").unwrap_or(0),
+            cpu_percent: process::cpu_percent("watch", stat, ticks_per_sec, counters),
+            threads: stat.threads,
+            fd_count: process::count_fds(paths, stat.pid).unwrap_or(0),
+            read_bytes,
+            write_bytes,
+        });
+    }
+
+    let read_bytes = processes.iter().map(|p| p.read_bytes).sum();
+    let write_bytes = processes.iter().map(|p| p.write_bytes).sum();
+
+    // tree-wide I/O drops when a child exits, which delta() treats as a reset for that interval
+    Ok(Some(ProcessTreeMetrics {
+        root_pid,
+        process_count: processes.len(),
+        rss_kb: processes.iter().map(|p| p.rss_kb).sum(),
+        swap_kb: processes.iter().map(|p| p.swap_kb).sum(),
+        cpu_percent: processes.iter().map(|p| p.cpu_percent).sum(),
+        threads: processes.iter().map(|p| p.threads).sum(),
+        fd_count: processes.iter().map(|p| p.fd_count).sum(),
+        read_bytes,
+        write_bytes,
+        read_bytes_per_sec: counters.rate("watch.read_bytes", read_bytes),
+        write_bytes_per_sec: counters.rate("watch.write_bytes", write_bytes),
+        processes,
+    }))
+}
+
+fn read_proc_io(paths: &HostPaths, pid: u32) -> Option<(u64, u64)> {
+    let content = fs::read_to_string(paths.pid(pid, "io")).ok()?;
+    let mut read_bytes = 0;
+    let mut write_bytes = 0;
+
+    for line in content.lines() {
+        match line.split_once(':') {
+            Some(("read_bytes", v)) => read_bytes = v.trim().parse().unwrap_or(0),
+            Some(("write_bytes", v)) => write_bytes = v.trim().parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+
+    Some((read_bytes, write_bytes))
+}